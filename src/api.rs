@@ -1,10 +1,18 @@
 use clap::ValueEnum;
-use serde::{Deserialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::Value;
-use std::{collections::HashMap, time::Duration};
-use ureq::{Agent, RequestBuilder, typestate::WithoutBody};
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+use ureq::Agent;
 
-use crate::decrypt_url;
+use crate::{decrypt_url, extractors, utils::days_from_civil};
+
+/// Query shared by search_anime() and the recent catalogue
+const SHOWS_GQL: &str = "query( $search: SearchInput $limit: Int $page: Int $translationType: VaildTranslationTypeEnumType $countryOrigin: VaildCountryOriginEnumType ) { shows( search: $search limit: $limit page: $page translationType: $translationType countryOrigin: $countryOrigin ) { edges { _id name englishName availableEpisodes __typename thumbnail description } }}";
 
 //  NOTE: Response from search_anime()
 #[derive(Deserialize, Debug, Clone)]
@@ -16,10 +24,8 @@ pub struct AnimeEdge {
 
     pub english_name: Option<String>,
     pub available_episodes: Option<HashMap<String, Value>>,
-    pub thumbnail: String,
+    #[serde(default)]
     pub description: String,
-    #[serde(rename = "__typename")]
-    pub typename: String,
 }
 
 #[derive(Deserialize, Debug)]
@@ -37,6 +43,29 @@ pub struct SearchResponse {
     pub data: DataWrapper,
 }
 
+//  NOTE: Response from browse_anime() for popular and top
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PopularCard {
+    pub any_card: Option<AnimeEdge>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PopularData {
+    pub recommendations: Vec<PopularCard>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PopularWrapper {
+    pub query_popular: PopularData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PopularResponse {
+    pub data: PopularWrapper,
+}
+
 //  NOTE: Response for get_episode_links()
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -63,13 +92,109 @@ pub struct EpisodeResponse {
 }
 
 //  NOTE: Response for get_episode_list()
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct AiredDate {
+    pub year: Option<i64>,
+    /// month of the year, starting at 1
+    pub month: Option<i64>,
+    pub date: Option<i64>,
+    pub hour: Option<i64>,
+    pub minute: Option<i64>,
+}
+
+impl AiredDate {
+    /// Seconds since the unix epoch, `None` when the date is incomplete
+    pub fn to_unix(self) -> Option<i64> {
+        let (year, month, date) = (self.year?, self.month?, self.date?);
+        let days = days_from_civil(year, month, date);
+        Some(days * 86400 + self.hour.unwrap_or(0) * 3600 + self.minute.unwrap_or(0) * 60)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Season {
+    pub quarter: Option<String>,
+    pub year: Option<i64>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedShow {
+    pub relation: String,
+    pub show_id: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct ShowDetail {
     #[serde(rename = "_id")]
     pub id: String,
     pub name: String,
-    #[serde(rename = "availableEpisodesDetail")]
+    pub english_name: Option<String>,
+    pub native_name: Option<String>,
+    pub alt_names: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub genres: Option<Vec<String>>,
+    pub status: Option<String>,
+    pub score: Option<f64>,
+    pub season: Option<Season>,
+    pub studios: Option<Vec<String>>,
+    /// runtime of an episode in milliseconds, sent either as number or string
+    pub episode_duration: Option<Value>,
+    pub aired_start: Option<AiredDate>,
+    /// milliseconds between two episodes, sent either as number or string
+    pub broadcast_interval: Option<Value>,
+    /// date the latest episode aired on, keyed by mode
+    pub last_episode_date: Option<HashMap<String, AiredDate>>,
+    pub related_shows: Option<Vec<RelatedShow>>,
     pub available_episodes_detail: HashMap<String, Vec<String>>,
+    /// ids on the trackers, sent either as number or string
+    pub ani_list_id: Option<Value>,
+    pub mal_id: Option<Value>,
+    /// per episode info keyed by episode string, filled by get_episode_list()
+    #[serde(skip)]
+    pub episode_infos: HashMap<String, EpisodeInfo>,
+    /// modes each episode exists in, filled by get_episode_list()
+    #[serde(skip)]
+    pub episode_modes: HashMap<String, Vec<Mode>>,
+}
+
+impl ShowDetail {
+    pub fn anilist_id(&self) -> Option<u64> {
+        value_as_u64(self.ani_list_id.as_ref()?)
+    }
+
+    pub fn mal_id(&self) -> Option<u64> {
+        value_as_u64(self.mal_id.as_ref()?)
+    }
+
+    /// Episode runtime in minutes
+    pub fn duration_minutes(&self) -> Option<u64> {
+        value_as_u64(self.episode_duration.as_ref()?).map(|ms| ms / 60_000)
+    }
+
+    /// Unix time the next episode of `mode` is expected at, going by the
+    /// date of the latest episode and the broadcast interval
+    pub fn next_episode_at(&self, mode: &str) -> Option<i64> {
+        if self.status.as_deref() != Some("Releasing") {
+            return None;
+        }
+
+        let last = self.last_episode_date.as_ref()?.get(mode)?.to_unix()?;
+        let interval = value_as_u64(self.broadcast_interval.as_ref()?)? as i64 / 1000;
+        if interval <= 0 {
+            return None;
+        }
+
+        Some(last + interval)
+    }
+}
+
+fn value_as_u64(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_f64().map(|f| f as u64))
+        .or_else(|| value.as_str()?.parse::<f64>().ok().map(|f| f as u64))
 }
 
 #[derive(Deserialize, Debug)]
@@ -82,25 +207,241 @@ pub struct EpisodeListResponse {
     pub data: ShowDetailData,
 }
 
+//  NOTE: Response for get_episode_infos()
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VidInfo {
+    /// runtime in seconds
+    pub vid_duration: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeInfo {
+    pub episode_id_num: f64,
+    /// title of the episode
+    pub notes: Option<String>,
+    pub thumbnails: Option<Vec<String>>,
+    /// iso 8601 upload dates keyed by mode
+    pub upload_dates: Option<HashMap<String, Value>>,
+    #[serde(rename = "vidInforssub")]
+    pub vid_info: Option<VidInfo>,
+}
+
+impl EpisodeInfo {
+    /// Upload date of `mode` as `YYYY-MM-DD`
+    pub fn upload_date(&self, mode: &str) -> Option<&str> {
+        let date = self.upload_dates.as_ref()?.get(mode)?.as_str()?;
+        date.get(..10)
+    }
+
+    pub fn duration_minutes(&self) -> Option<u64> {
+        let secs = self.vid_info.as_ref()?.vid_duration?;
+        Some((secs / 60.0).round() as u64)
+    }
+
+    /// Absolute url of the first thumbnail
+    pub fn thumbnail_url(&self) -> Option<String> {
+        let thumbnail = self.thumbnails.as_ref()?.first()?;
+        if thumbnail.starts_with("http") {
+            Some(thumbnail.to_string())
+        } else {
+            Some(format!(
+                "https://wp.youtube-anime.com/aln.youtube-anime.com/{}",
+                thumbnail.trim_start_matches('/')
+            ))
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct EpisodeInfosData {
+    pub episode_infos: Vec<EpisodeInfo>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EpisodeInfosResponse {
+    pub data: EpisodeInfosData,
+}
+
+//  NOTE: Response for get_franchise()
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FranchiseShow {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub name: String,
+    pub english_name: Option<String>,
+    /// TV, Movie, OVA, ONA, Special...
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub aired_start: Option<AiredDate>,
+    /// how the show relates to the one the franchise was requested for
+    #[serde(skip)]
+    pub relation: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ShowsWithIdsData {
+    pub shows_with_ids: Vec<FranchiseShow>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FranchiseResponse {
+    pub data: ShowsWithIdsData,
+}
+
+//  NOTE: Response for get_airing()
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AiringData {
+    pub shows_with_ids: Vec<ShowDetail>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AiringResponse {
+    pub data: AiringData,
+}
+
+/// Episodes whose infos are fetched in one request
+pub const EPISODE_INFO_PAGE: usize = 100;
+
+/// Show metadata along with the episodes available in the requested mode
+pub type EpisodeList = (ShowDetail, Vec<String>);
+
+/// External subtitle track served next to a stream
+#[derive(Deserialize, Debug, Clone)]
+pub struct Subtitle {
+    pub lang: String,
+    pub label: Option<String>,
+    pub src: String,
+}
+
+/// Stream url that can be handed to the player, with its subtitle tracks
+#[derive(Debug, Clone)]
+pub struct ResolvedLink {
+    pub url: String,
+    /// resolution reported by the source such as `1080p`
+    pub quality: Option<String>,
+    pub subtitles: Vec<Subtitle>,
+    /// headers the host expects, such as its own `Referer`
+    pub headers: Vec<(String, String)>,
+}
+
+impl ResolvedLink {
+    /// Value of the header `name` the stream has to be requested with
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Subtitle track whose language code or label is `lang`, falling back to the first one
+pub fn preferred_subtitle<'a>(subtitles: &'a [Subtitle], lang: &str) -> Option<&'a Subtitle> {
+    subtitles
+        .iter()
+        .find(|s| {
+            s.lang.eq_ignore_ascii_case(lang)
+                || s.label
+                    .as_deref()
+                    .is_some_and(|l| l.eq_ignore_ascii_case(lang))
+        })
+        .or(subtitles.first())
+}
+
+/// Links that point to a clock.json which has to be resolved into the stream url
+fn needs_resolving(url: &str) -> bool {
+    url.contains("clock.json") || url.contains("https://allanime.day")
+}
+
+/// Outcome of requesting the first byte of a stream
+#[derive(Debug, Clone)]
+pub struct Probe {
+    /// http status, `None` when the link could not be reached
+    pub status: Option<u16>,
+    pub latency: Duration,
+    pub content_type: Option<String>,
+    /// size of the whole stream in bytes
+    pub size: Option<u64>,
+}
+
+impl Probe {
+    pub fn is_alive(&self) -> bool {
+        self.status.is_some_and(|status| status < 400)
+    }
+}
+
+/// Episode string along with (provider name, url) pairs
+pub type ProviderList = (String, Vec<(String, String)>);
+
 #[derive(Debug)]
 pub struct Api {
     pub base_api: &'static str,
     pub referer: &'static str,
     pub user_agent: &'static str,
-    pub mode: &'static str,
     pub debug: bool,
     agent: Agent,
 }
 
-#[derive(ValueEnum, Debug, Clone, Copy)]
+#[derive(ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum Mode {
     Sub,
     Dub,
     Raw,
 }
 
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Sub, Mode::Dub, Mode::Raw];
+
+    /// Translation type as the api names it
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Mode::Sub => "sub",
+            Mode::Dub => "dub",
+            Mode::Raw => "raw",
+        }
+    }
+
+    /// Mode that follows this one when cycling through them
+    pub fn next(&self) -> Mode {
+        match self {
+            Mode::Sub => Mode::Dub,
+            Mode::Dub => Mode::Raw,
+            Mode::Raw => Mode::Sub,
+        }
+    }
+}
+
+/// Catalogues that can be browsed without a search query
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Browse {
+    /// Most watched this week
+    Popular,
+    /// Shows with recently released episodes
+    Recent,
+    /// Most watched of all time
+    Top,
+}
+
+impl Browse {
+    pub const ALL: [Browse; 3] = [Browse::Popular, Browse::Recent, Browse::Top];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Browse::Popular => "Popular",
+            Browse::Recent => "Recent",
+            Browse::Top => "Top",
+        }
+    }
+}
+
 impl Api {
-    pub fn new(mode: Mode, debug: bool) -> Self {
+    pub fn new(debug: bool) -> Self {
         let user_agent = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) Gecko/20100101 Firefox/121.0";
         let config = Agent::config_builder()
             .timeout_per_call(Some(Duration::from_secs(12)))
@@ -112,11 +453,6 @@ impl Api {
             base_api: "https://api.allanime.day/api",
             referer: "https://allmanga.to",
             user_agent,
-            mode: match mode {
-                Mode::Sub => "sub",
-                Mode::Dub => "dub",
-                Mode::Raw => "raw",
-            },
             debug,
             agent: Agent::new_with_config(config),
         }
@@ -145,30 +481,76 @@ impl Api {
     }
 
     /// Search for anime with its name
-    pub fn search_anime(&self, query: &str) -> Result<SearchResponse, Box<dyn std::error::Error>> {
-        let gql = "query( $search: SearchInput $limit: Int $page: Int $translationType: VaildTranslationTypeEnumType $countryOrigin: VaildCountryOriginEnumType ) { shows( search: $search limit: $limit page: $page translationType: $translationType countryOrigin: $countryOrigin ) { edges { _id name englishName availableEpisodes __typename thumbnail description } }}";
-
+    pub fn search_anime(
+        &self,
+        query: &str,
+        mode: Mode,
+    ) -> Result<SearchResponse, Box<dyn std::error::Error>> {
         let variables_json = &format!(
-            r#"{{"search":{{"allowAdult":false,"allowUnknown":false,"query":"{}"}},"limit":40,"page":1,"translationType":"{}","countryOrigin":"ALL"}}"#,
-            query, self.mode
+            r#"{{"search":{{"allowAdult":false,"allowUnknown":false,"query":{}}},"limit":40,"page":1,"translationType":"{}","countryOrigin":"ALL"}}"#,
+            // typed from inside the tui, so it has to be escaped
+            Value::from(query),
+            mode.as_str()
         );
 
-        let resp: SearchResponse = self.request_api(variables_json, gql)?;
+        let resp: SearchResponse = self.request_api(variables_json, SHOWS_GQL)?;
 
         Ok(resp)
     }
 
+    /// Browse a catalogue of anime, no query required
+    pub fn browse_anime(
+        &self,
+        browse: Browse,
+        mode: Mode,
+    ) -> Result<Vec<AnimeEdge>, Box<dyn std::error::Error>> {
+        let date_range = match browse {
+            Browse::Recent => {
+                let variables_json = &format!(
+                    r#"{{"search":{{"allowAdult":false,"allowUnknown":false,"sortBy":"Recent"}},"limit":40,"page":1,"translationType":"{}","countryOrigin":"ALL"}}"#,
+                    mode.as_str()
+                );
+
+                let resp: SearchResponse = self.request_api(variables_json, SHOWS_GQL)?;
+                return Ok(resp.data.shows.edges);
+            }
+            Browse::Popular => 7,
+            // a date range of 0 ranks over all time
+            Browse::Top => 0,
+        };
+
+        let gql = "query( $type: VaildPopularTypeEnumType! $size: Int! $page: Int $dateRange: Int ) { queryPopular( type: $type size: $size dateRange: $dateRange page: $page ) { recommendations { anyCard { _id name englishName availableEpisodes __typename thumbnail description } } }}";
+
+        let variables_json = &format!(
+            r#"{{"type":"anime","size":40,"dateRange":{},"page":1}}"#,
+            date_range
+        );
+
+        let resp: PopularResponse = self.request_api(variables_json, gql)?;
+
+        Ok(resp
+            .data
+            .query_popular
+            .recommendations
+            .into_iter()
+            .filter_map(|card| card.any_card)
+            .collect())
+    }
+
     /// Get the links that can be played/download
     pub fn get_episode_links(
         &self,
         id: &str,
         ep: &str,
-    ) -> Result<(String, Vec<(String, String)>), Box<dyn std::error::Error>> {
+        mode: Mode,
+    ) -> Result<ProviderList, Box<dyn std::error::Error>> {
         let gql = "query ($showId: String!, $translationType: VaildTranslationTypeEnumType!, $episodeString: String!) { episode( showId: $showId translationType: $translationType episodeString: $episodeString ) { episodeString sourceUrls }}";
 
         let variables_json = &format!(
             r#"{{"showId":"{}","translationType":"{}","episodeString":"{}"}}"#,
-            id, self.mode, ep
+            id,
+            mode.as_str(),
+            ep
         );
 
         let resp: EpisodeResponse = self.request_api(variables_json, gql)?;
@@ -179,7 +561,13 @@ impl Api {
             let raw_uri = source.source_url;
 
             let uri = if let Some(stripped) = raw_uri.strip_prefix("--") {
-                decrypt_url(stripped)
+                match decrypt_url(stripped) {
+                    Ok(uri) => uri,
+                    Err(e) => {
+                        eprintln!("Error decoding {} link: {}", provider_name, e);
+                        continue;
+                    }
+                }
             } else if let Some(stripped) = raw_uri.strip_prefix("//") {
                 format!("https:{}", stripped)
             } else {
@@ -199,7 +587,7 @@ impl Api {
             };
 
             if self.debug {
-                unimplemented!()
+                eprintln!("{}: {}", provider_name, uri);
             }
 
             vec.push((provider_name, uri));
@@ -208,46 +596,120 @@ impl Api {
         Ok((resp.data.episode.episode_string, vec))
     }
 
-    pub fn resolve_clock_urls(&self, url: &str) -> Result<String, Box<dyn std::error::Error>> {
-        print!("running");
-
+    pub fn resolve_clock_urls(
+        &self,
+        url: &str,
+    ) -> Result<ResolvedLink, Box<dyn std::error::Error>> {
         let resp = self.agent.get(url).call()?;
         let json: serde_json::Value = resp.into_body().read_json()?;
 
-        if let Some(links_array) = json["links"].as_array() {
-            if let Some(first_item) = links_array.first() {
-                if let Some(wixmp_url) = first_item["link"].as_str() {
-                    return Ok(wixmp_url.to_string());
+        if let Some(links_array) = json["links"].as_array()
+            && let Some(first_item) = links_array.first()
+            && let Some(wixmp_url) = first_item["link"].as_str()
+        {
+            let mut subtitles: Vec<Subtitle> =
+                serde_json::from_value(first_item["subtitles"].clone()).unwrap_or_default();
+            for subtitle in &mut subtitles {
+                if let Some(stripped) = subtitle.src.strip_prefix("//") {
+                    subtitle.src = format!("https://{}", stripped);
                 }
             }
-        }
 
-        // json["links"]
-        //     .as_array()
-        //     .and_then(|arr| arr.first())
-        //     .and_then(|item| item["link"].as_str())
-        //     .map(|s| s.to_string());
+            return Ok(ResolvedLink {
+                url: wixmp_url.to_string(),
+                quality: first_item["resolutionStr"].as_str().map(str::to_string),
+                subtitles,
+                headers: Vec::new(),
+            });
+        }
 
         Err("Could not find 'link' field in clock.json response".into())
     }
 
+    /// AniList and MyAnimeList ids of a show
+    pub fn get_tracker_ids(
+        &self,
+        id: &str,
+    ) -> Result<(Option<u64>, Option<u64>), Box<dyn std::error::Error>> {
+        let gql = "query ($showId: String!) { show( _id: $showId ) { aniListId malId }}";
+        let variables_json = &format!(r#"{{"showId":"{}"}}"#, id);
+
+        let resp: Value = self.request_api(variables_json, gql)?;
+        let show = &resp["data"]["show"];
+        Ok((
+            value_as_u64(&show["aniListId"]),
+            value_as_u64(&show["malId"]),
+        ))
+    }
+
+    /// Turn the link of `provider` into something the player can open
+    pub fn resolve_link(
+        &self,
+        provider: &str,
+        url: &str,
+    ) -> Result<ResolvedLink, Box<dyn std::error::Error>> {
+        if needs_resolving(url) {
+            return self.resolve_clock_urls(url);
+        }
+
+        let Some(extractor) = extractors::find(provider) else {
+            return Ok(ResolvedLink {
+                url: url.to_string(),
+                quality: None,
+                subtitles: Vec::new(),
+                headers: Vec::new(),
+            });
+        };
+
+        let page = self
+            .agent
+            .get(url)
+            .header("Referer", self.referer)
+            .call()?
+            .into_body()
+            .read_to_string()?;
+        let stream = (extractor.extract)(&page, url)?
+            .into_iter()
+            .next()
+            .ok_or("embed page has no stream")?;
+
+        Ok(ResolvedLink {
+            url: stream.url,
+            quality: stream.quality,
+            subtitles: Vec::new(),
+            headers: stream.headers,
+        })
+    }
+
     /// Get list of episodes available from api
     pub fn get_episode_list(
         &self,
         id: &str,
-    ) -> Result<(String, Vec<String>, String), Box<dyn std::error::Error>> {
-        let gql =
-            "query ($showId: String!) { show( _id: $showId ) { _id name availableEpisodesDetail }}";
+        mode: Mode,
+    ) -> Result<EpisodeList, Box<dyn std::error::Error>> {
+        let gql = "query ($showId: String!) { show( _id: $showId ) { _id name englishName nativeName altNames description genres status score season studios episodeDuration airedStart broadcastInterval lastEpisodeDate relatedShows availableEpisodesDetail aniListId malId }}";
         let variables_json = &format!(r#"{{"showId":"{}"}}"#, id);
 
         let resp: EpisodeListResponse = self.request_api(variables_json, gql)?;
 
         let mut show = resp.data.show;
 
+        for mode in Mode::ALL {
+            for ep in show
+                .available_episodes_detail
+                .get(mode.as_str())
+                .into_iter()
+                .flatten()
+            {
+                show.episode_modes.entry(ep.clone()).or_default().push(mode);
+            }
+        }
+
         let mut episodes = show
             .available_episodes_detail
-            .remove(self.mode)
-            .ok_or(format!("No episodes found for mode '{}'", self.mode))?;
+            .get(mode.as_str())
+            .cloned()
+            .ok_or(format!("No episodes found for mode '{}'", mode.as_str()))?;
 
         episodes.sort_by(|a, b| {
             let a_num = a.parse::<f64>().unwrap_or(0.0);
@@ -257,10 +719,175 @@ impl Api {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        // only the first page, the others are fetched as the selection reaches them
+        let page = &episodes[..episodes.len().min(EPISODE_INFO_PAGE)];
+        if let (Some(first), Some(last)) = (page.first(), page.last()) {
+            // not every show has episode infos, the list is still usable without them
+            show.episode_infos = self
+                .get_episode_infos(&show.id, first, last)
+                .unwrap_or_default();
+        }
+
         if self.debug {
-            unimplemented!()
+            eprintln!(
+                "{} ({}): {} {} episodes, infos for {}",
+                show.name,
+                show.id,
+                episodes.len(),
+                mode.as_str(),
+                show.episode_infos.len()
+            );
+        }
+
+        Ok((show, episodes))
+    }
+
+    /// Get the shows related to `show`, itself included, in airing order
+    pub fn get_franchise(
+        &self,
+        show: &ShowDetail,
+    ) -> Result<Vec<FranchiseShow>, Box<dyn std::error::Error>> {
+        let mut relations: HashMap<&str, &str> = show
+            .related_shows
+            .iter()
+            .flatten()
+            .map(|r| (r.show_id.as_str(), r.relation.as_str()))
+            .collect();
+        relations.insert(&show.id, "this");
+
+        let gql = "query ($ids: [String!]!) { showsWithIds(ids: $ids) { _id name englishName type airedStart }}";
+        let variables_json =
+            &serde_json::json!({ "ids": relations.keys().collect::<Vec<_>>() }).to_string();
+
+        let resp: FranchiseResponse = self.request_api(variables_json, gql)?;
+
+        let mut shows = resp.data.shows_with_ids;
+        for entry in &mut shows {
+            entry.relation = relations
+                .get(entry.id.as_str())
+                .unwrap_or(&"other")
+                .to_string();
+        }
+        // shows without an air date are usually unannounced sequels
+        shows.sort_by_key(|entry| {
+            entry
+                .aired_start
+                .and_then(AiredDate::to_unix)
+                .unwrap_or(i64::MAX)
+        });
+
+        Ok(shows)
+    }
+
+    /// Airing status and schedule of the shows with `ids`
+    pub fn get_airing(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<ShowDetail>, Box<dyn std::error::Error>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
         }
 
-        Ok((show.name, episodes, show.id))
+        let gql = "query ($ids: [String!]!) { showsWithIds(ids: $ids) { _id name englishName status broadcastInterval lastEpisodeDate availableEpisodesDetail }}";
+        let variables_json = &serde_json::json!({ "ids": ids }).to_string();
+
+        let resp: AiringResponse = self.request_api(variables_json, gql)?;
+        Ok(resp.data.shows_with_ids)
+    }
+
+    /// Get titles, thumbnails and dates of the episodes from `first` to `last`
+    pub fn get_episode_infos(
+        &self,
+        id: &str,
+        first: &str,
+        last: &str,
+    ) -> Result<HashMap<String, EpisodeInfo>, Box<dyn std::error::Error>> {
+        let gql = "query ($showId: String!, $episodeNumStart: Float!, $episodeNumEnd: Float!) { episodeInfos( showId: $showId episodeNumStart: $episodeNumStart episodeNumEnd: $episodeNumEnd ) { episodeIdNum notes thumbnails uploadDates vidInforssub }}";
+        let variables_json = &format!(
+            r#"{{"showId":"{}","episodeNumStart":{},"episodeNumEnd":{}}}"#,
+            id,
+            first.parse::<f64>()?,
+            last.parse::<f64>()?
+        );
+
+        let resp: EpisodeInfosResponse = self.request_api(variables_json, gql)?;
+
+        // f64 display matches episode strings, 12.0 is "12" and 12.5 is "12.5"
+        Ok(resp
+            .data
+            .episode_infos
+            .into_iter()
+            .map(|info| (info.episode_id_num.to_string(), info))
+            .collect())
+    }
+
+    /// Download a subtitle track to `path`
+    pub fn download_subtitle(
+        &self,
+        subtitle: &Subtitle,
+        path: &Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let resp = self
+            .agent
+            .get(&subtitle.src)
+            .header("Referer", self.referer)
+            .call()?;
+        fs::write(path, resp.into_body().read_to_vec()?)?;
+        Ok(())
+    }
+
+    /// Download an image such as an episode thumbnail
+    pub fn get_image(&self, url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let resp = self.agent.get(url).header("Referer", self.referer).call()?;
+        Ok(resp.into_body().read_to_vec()?)
+    }
+
+    /// Check that the link of `provider` serves something, resolving it first
+    pub fn probe_link(&self, provider: &str, url: &str) -> Probe {
+        let started = Instant::now();
+        let dead = |started: Instant| Probe {
+            status: None,
+            latency: started.elapsed(),
+            content_type: None,
+            size: None,
+        };
+
+        let Ok(link) = self.resolve_link(provider, url) else {
+            return dead(started);
+        };
+
+        // a single byte is enough to know the stream is there and how big it is
+        let started = Instant::now();
+        let resp = self
+            .agent
+            .get(&link.url)
+            .header("Referer", link.header("Referer").unwrap_or(self.referer))
+            .header("Range", "bytes=0-0")
+            .config()
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(6)))
+            .build()
+            .call();
+        let Ok(resp) = resp else {
+            return dead(started);
+        };
+
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        // `bytes 0-0/12345` holds the full size, content-length is 1 in that case
+        let size = header("content-range")
+            .and_then(|range| range.rsplit_once('/')?.1.parse().ok())
+            .or_else(|| header("content-length")?.parse().ok());
+
+        Probe {
+            status: Some(resp.status().as_u16()),
+            latency: started.elapsed(),
+            content_type: header("content-type"),
+            size,
+        }
     }
 }
@@ -0,0 +1,39 @@
+//! Settings read from `config.json` in the config directory
+
+use serde::Deserialize;
+use std::{fs, io, path::PathBuf};
+
+use crate::utils::config_dir;
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Config {
+    /// providers to play without asking, in order of preference
+    pub providers: Vec<String>,
+    /// where the default downloader saves episodes
+    pub download_dir: Option<PathBuf>,
+    /// oauth access token to push progress to AniList
+    pub anilist_token: Option<String>,
+    /// oauth access token to push progress to MyAnimeList
+    pub mal_token: Option<String>,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("config.json"))
+    }
+
+    /// Load the config file, a missing file is an empty config
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("invalid config {}: {}", path.display(), e).into()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
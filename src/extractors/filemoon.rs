@@ -0,0 +1,36 @@
+//! filemoon, allanime source `Fm-Hls`
+
+use super::{Stream, jwplayer_file};
+use std::error::Error;
+
+/// Same packed jwplayer setup as streamwish, packed with base 62
+pub fn extract(page: &str, url: &str) -> Result<Vec<Stream>, Box<dyn Error>> {
+    jwplayer_file(page, url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_packed_playlist() {
+        let streams = extract(
+            include_str!("fixtures/filemoon.html"),
+            "https://filemoon.sx/e/zz",
+        )
+        .unwrap();
+        assert_eq!(
+            streams[0].url,
+            "https://be6721.rcr72.waw04.cdn112.com/hls2/03/00999/zz_o/master.m3u8?t=Qq1"
+        );
+        assert_eq!(
+            streams[0].headers,
+            [("Referer".to_string(), "https://filemoon.sx/".to_string())]
+        );
+    }
+
+    #[test]
+    fn fails_without_player() {
+        assert!(extract("<p>not found</p>", "https://filemoon.sx/e/zz").is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Turn the embed pages of allanime sources into direct stream urls
+//!
+//! Every extractor only parses the page, downloading it is left to the api so
+//! the parsers can be tested against saved pages.
+
+mod filemoon;
+mod mp4upload;
+mod okru;
+mod packer;
+mod streamwish;
+
+use std::error::Error;
+
+/// Direct stream url along with the headers the host checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stream {
+    pub url: String,
+    /// resolution such as `1080p` when the page tells it
+    pub quality: Option<String>,
+    pub headers: Vec<(String, String)>,
+}
+
+/// Parser of an embed `page` downloaded from `url`
+pub type Extract = fn(page: &str, url: &str) -> Result<Vec<Stream>, Box<dyn Error>>;
+
+pub struct Extractor {
+    /// allanime `sourceName`s served by this host
+    pub sources: &'static [&'static str],
+    /// streams found in the page, best first
+    pub extract: Extract,
+}
+
+pub const EXTRACTORS: &[Extractor] = &[
+    Extractor {
+        sources: &["Mp4"],
+        extract: mp4upload::extract,
+    },
+    Extractor {
+        sources: &["Ok"],
+        extract: okru::extract,
+    },
+    Extractor {
+        sources: &["Sw"],
+        extract: streamwish::extract,
+    },
+    Extractor {
+        sources: &["Fm-Hls"],
+        extract: filemoon::extract,
+    },
+];
+
+/// Extractor for the allanime `sourceName`, `None` for links that are already direct
+pub fn find(source_name: &str) -> Option<&'static Extractor> {
+    EXTRACTORS.iter().find(|e| {
+        e.sources
+            .iter()
+            .any(|source| source.eq_ignore_ascii_case(source_name))
+    })
+}
+
+/// Quoted string of the first `key: "..."` in `text`, skipping keys inside longer words
+fn quoted_after<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.match_indices(key).find_map(|(at, _)| {
+        // such as `profile`, `filemoon`, `file_code` or the packer's `|file|`
+        let before = text[..at].chars().next_back();
+        if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        let rest = &text[at + key.len()..];
+        // the key may be quoted itself, as in json
+        let rest = rest.strip_prefix(['"', '\'']).unwrap_or(rest);
+        let rest = rest.trim_start().strip_prefix(':')?.trim_start();
+        let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        let rest = &rest[1..];
+        Some(&rest[..rest.find(quote)?])
+    })
+}
+
+/// `scheme://host/` of `url`
+fn origin(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let host = rest.split('/').next()?;
+    Some(format!("{}://{}/", scheme, host))
+}
+
+/// Stream of the first jwplayer `file:` in the page, unpacking packed scripts first
+fn jwplayer_file(page: &str, url: &str) -> Result<Vec<Stream>, Box<dyn Error>> {
+    let scripts = page
+        .split("eval(function(p,a,c,k,e,d)")
+        .skip(1)
+        .filter_map(packer::unpack);
+
+    let file = std::iter::once(page.to_string())
+        .chain(scripts)
+        .find_map(|script| quoted_after(&script, "file").map(str::to_string))
+        .ok_or("no jwplayer file in embed page")?;
+
+    Ok(vec![Stream {
+        url: file,
+        quality: None,
+        headers: vec![("Referer".to_string(), origin(url).ok_or("bad embed url")?)],
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_extractor_by_source_name() {
+        assert!(find("mp4").is_some());
+        assert!(find("Fm-Hls").is_some());
+        assert!(find("Default").is_none());
+    }
+
+    #[test]
+    fn reads_quoted_values() {
+        assert_eq!(quoted_after(r#"src: "a.mp4""#, "src"), Some("a.mp4"));
+        assert_eq!(quoted_after("file:'b.m3u8',", "file"), Some("b.m3u8"));
+        assert_eq!(quoted_after("file: b", "file"), None);
+        assert_eq!(quoted_after(r#"{"file": "c.mp4"}"#, "file"), Some("c.mp4"));
+    }
+
+    #[test]
+    fn skips_keys_inside_other_words() {
+        let page = r#"var filemoon = 'no'; file_code: 'no'; profile: 'no';
+            k = '|file|sources'.split('|'); file = 'no';
+            jwplayer("vplayer").setup({ file:"real.m3u8" });"#;
+        assert_eq!(quoted_after(page, "file"), Some("real.m3u8"));
+    }
+
+    #[test]
+    fn origin_of_url() {
+        assert_eq!(
+            origin("https://ok.ru/videoembed/1").as_deref(),
+            Some("https://ok.ru/")
+        );
+    }
+}
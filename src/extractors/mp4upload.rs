@@ -0,0 +1,51 @@
+//! mp4upload.com, allanime source `Mp4`
+
+use super::{Stream, quoted_after};
+use std::error::Error;
+
+/// The video.js setup holds the mp4, which is only served with the mp4upload referer
+pub fn extract(page: &str, _url: &str) -> Result<Vec<Stream>, Box<dyn Error>> {
+    let setup = &page[page
+        .find("player.src(")
+        .ok_or("no player source in embed page")?..];
+    let src = quoted_after(setup, "src").ok_or("no video url in player source")?;
+
+    Ok(vec![Stream {
+        url: src.to_string(),
+        quality: None,
+        headers: vec![(
+            "Referer".to_string(),
+            "https://www.mp4upload.com/".to_string(),
+        )],
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_mp4() {
+        let streams = extract(
+            include_str!("fixtures/mp4upload.html"),
+            "https://www.mp4upload.com/embed-q1w2e3r4t5y6.html",
+        )
+        .unwrap();
+        assert_eq!(
+            streams,
+            [Stream {
+                url: "https://a4.mp4upload.com:183/d/xkx2c3nmz3b4quuoxsxwsvbe5ppp7n5i6ilhr46qxnbf3g4wfm7qaxnh/video.mp4".to_string(),
+                quality: None,
+                headers: vec![(
+                    "Referer".to_string(),
+                    "https://www.mp4upload.com/".to_string()
+                )],
+            }]
+        );
+    }
+
+    #[test]
+    fn fails_on_removed_video() {
+        assert!(extract("<h2>File was deleted</h2>", "").is_err());
+    }
+}
@@ -0,0 +1,120 @@
+//! ok.ru, allanime source `Ok`
+
+use super::Stream;
+use serde::Deserialize;
+use std::error::Error;
+
+#[derive(Deserialize)]
+struct Options {
+    flashvars: Flashvars,
+}
+
+#[derive(Deserialize)]
+struct Flashvars {
+    /// json encoded again inside the options
+    metadata: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    #[serde(default)]
+    videos: Vec<Video>,
+    hls_manifest_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Video {
+    name: String,
+    url: String,
+    #[serde(default)]
+    disallowed: bool,
+}
+
+/// ok.ru names its renditions instead of giving the height
+fn quality(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "mobile" => "144p",
+        "lowest" => "240p",
+        "low" => "360p",
+        "sd" => "480p",
+        "hd" => "720p",
+        "full" => "1080p",
+        "quad" => "1440p",
+        "ultra" => "2160p",
+        _ => return None,
+    })
+}
+
+/// Renditions are listed in the html escaped `data-options` attribute of the player
+pub fn extract(page: &str, _url: &str) -> Result<Vec<Stream>, Box<dyn Error>> {
+    let attr = "data-options=\"";
+    let options = &page[page.find(attr).ok_or("no player options in embed page")? + attr.len()..];
+    let options = &options[..options.find('"').ok_or("unterminated player options")?];
+    let options = options.replace("&quot;", "\"").replace("&amp;", "&");
+
+    let options: Options = serde_json::from_str(&options)?;
+    let metadata: Metadata = serde_json::from_str(&options.flashvars.metadata)?;
+
+    let referer = vec![("Referer".to_string(), "https://ok.ru/".to_string())];
+    let mut videos: Vec<Video> = metadata
+        .videos
+        .into_iter()
+        .filter(|v| !v.disallowed)
+        .collect();
+    // listed from the lowest resolution up
+    videos.reverse();
+
+    let mut streams: Vec<Stream> = videos
+        .into_iter()
+        .map(|v| Stream {
+            quality: quality(&v.name).map(str::to_string),
+            url: v.url,
+            headers: referer.clone(),
+        })
+        .collect();
+    streams.extend(metadata.hls_manifest_url.map(|url| Stream {
+        url,
+        quality: None,
+        headers: referer.clone(),
+    }));
+
+    if streams.is_empty() {
+        return Err("no playable video in player options".into());
+    }
+    Ok(streams)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_renditions_best_first() {
+        let streams = extract(
+            include_str!("fixtures/okru.html"),
+            "https://ok.ru/videoembed/7000001",
+        )
+        .unwrap();
+
+        let qualities: Vec<_> = streams.iter().map(|s| s.quality.as_deref()).collect();
+        assert_eq!(
+            qualities,
+            [Some("720p"), Some("480p"), Some("240p"), Some("144p"), None]
+        );
+        assert_eq!(
+            streams[0].url,
+            "https://vd318.okcdn.ru/?expires=1700000000&srcIp=1.2.3.4&type=3&id=7000001"
+        );
+        assert!(streams[4].url.contains("video.m3u8"));
+        assert_eq!(
+            streams[0].headers,
+            [("Referer".to_string(), "https://ok.ru/".to_string())]
+        );
+    }
+
+    #[test]
+    fn fails_without_player() {
+        assert!(extract("<html></html>", "").is_err());
+    }
+}
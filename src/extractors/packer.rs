@@ -0,0 +1,85 @@
+//! Reverse of Dean Edwards' `eval(function(p,a,c,k,e,d)` javascript packer
+
+/// Unpack the arguments following a packer function: `}('payload',radix,count,'words'.split('|'))`
+pub fn unpack(script: &str) -> Option<String> {
+    let args = &script[script.find("}('")? + 3..];
+    let args = &args[..args.find("'.split('|')")?];
+
+    let (head, words) = args.rsplit_once(",'")?;
+    let (head, _count) = head.rsplit_once(',')?;
+    let (payload, radix) = head.rsplit_once("',")?;
+    let radix: u32 = radix.trim().parse().ok()?;
+    let words: Vec<&str> = words.split('|').collect();
+    let payload = payload.replace("\\'", "'");
+
+    let mut out = String::with_capacity(payload.len());
+    let mut word = String::new();
+    for c in payload.chars().chain(std::iter::once('\0')) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        let replacement = decode(&word, radix)
+            .and_then(|index| words.get(index))
+            .filter(|w| !w.is_empty());
+        out.push_str(replacement.unwrap_or(&word.as_str()));
+        word.clear();
+        if c != '\0' {
+            out.push(c);
+        }
+    }
+
+    Some(out)
+}
+
+/// Value of `word` in the packer's base, digits after `z` are `A`-`Z`
+fn decode(word: &str, radix: u32) -> Option<usize> {
+    if word.is_empty() {
+        return None;
+    }
+    word.chars().try_fold(0usize, |acc, c| {
+        let digit = match c {
+            '0'..='9' => c as u32 - '0' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 10,
+            'A'..='Z' if radix > 36 => c as u32 - 'A' as u32 + 36,
+            _ => return None,
+        };
+        if digit >= radix {
+            return None;
+        }
+        acc.checked_mul(radix as usize)?.checked_add(digit as usize)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpacks_words() {
+        let script = r"}('0 1(\'2\')',3,3,'var|f|x'.split('|')))";
+        assert_eq!(unpack(script).as_deref(), Some("var f('x')"));
+    }
+
+    #[test]
+    fn keeps_words_without_replacement() {
+        let script = r"}('0.1 a',36,2,'|file'.split('|')))";
+        assert_eq!(unpack(script).as_deref(), Some("0.file a"));
+    }
+
+    #[test]
+    fn decodes_base62() {
+        assert_eq!(decode("Z", 62), Some(61));
+        assert_eq!(decode("10", 62), Some(62));
+        assert_eq!(decode("Z", 36), None);
+    }
+
+    #[test]
+    fn long_words_stay_as_they_are() {
+        let word = "z".repeat(40);
+        assert_eq!(decode(&word, 62), None);
+
+        let script = format!("}}('{} 0',62,1,'x'.split('|')))", word);
+        assert_eq!(unpack(&script), Some(format!("{} x", word)));
+    }
+}
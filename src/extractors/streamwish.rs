@@ -0,0 +1,38 @@
+//! streamwish and its mirror domains, allanime source `Sw`
+
+use super::{Stream, jwplayer_file};
+use std::error::Error;
+
+/// The hls playlist sits in a packed jwplayer setup and wants the embed host as referer
+pub fn extract(page: &str, url: &str) -> Result<Vec<Stream>, Box<dyn Error>> {
+    jwplayer_file(page, url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_packed_playlist() {
+        let streams = extract(
+            include_str!("fixtures/streamwish.html"),
+            "https://streamwish.to/e/abcd",
+        )
+        .unwrap();
+        assert_eq!(
+            streams[0].url,
+            "https://cdn.swdyu.com/hls2/01/00123/abcd_n/master.m3u8?t=Xy9&s=1700000000"
+        );
+        assert_eq!(
+            streams[0].headers,
+            [("Referer".to_string(), "https://streamwish.to/".to_string())]
+        );
+    }
+
+    #[test]
+    fn extracts_plain_setup() {
+        let page = r#"<script>jwplayer("vplayer").setup({sources:[{file:"https://x.com/a.m3u8"}]});</script>"#;
+        let streams = extract(page, "https://awish.pro/e/x").unwrap();
+        assert_eq!(streams[0].url, "https://x.com/a.m3u8");
+    }
+}
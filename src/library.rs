@@ -0,0 +1,165 @@
+//! Downloaded episodes, recorded in `manifest.json` next to the files
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::utils::data_dir;
+
+/// An episode saved by the downloader
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// file name inside the download directory
+    pub file: String,
+    pub show_id: String,
+    pub show_name: String,
+    pub episode: String,
+    /// sub, dub or raw
+    pub mode: String,
+    /// subtitle file names inside the download directory
+    #[serde(default)]
+    pub subtitles: Vec<String>,
+    #[serde(default)]
+    pub duration_minutes: Option<u64>,
+    #[serde(default)]
+    pub watched: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Library {
+    pub dir: PathBuf,
+    /// entries whose file still exists, ordered by show then episode
+    pub entries: Vec<Entry>,
+    /// entries whose file is gone, kept in the manifest in case it comes back
+    missing: Vec<Entry>,
+}
+
+impl Library {
+    /// `download_dir` from the config, or `downloads` in the data directory
+    pub fn default_dir(configured: Option<PathBuf>) -> Option<PathBuf> {
+        configured.or_else(|| data_dir().map(|dir| dir.join("downloads")))
+    }
+
+    fn manifest(dir: &Path) -> PathBuf {
+        dir.join("manifest.json")
+    }
+
+    /// Read the manifest of `dir`, hiding files that were deleted or moved since
+    pub fn load(dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = Self::manifest(dir);
+        let entries: Vec<Entry> = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| format!("invalid manifest {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let (entries, missing) = entries
+            .into_iter()
+            .partition(|entry| dir.join(&entry.file).is_file());
+
+        let mut library = Self {
+            dir: dir.to_path_buf(),
+            entries,
+            missing,
+        };
+        library.sort();
+        Ok(library)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(
+            Self::manifest(&self.dir),
+            serde_json::to_string_pretty(
+                &self.entries.iter().chain(&self.missing).collect::<Vec<_>>(),
+            )?,
+        )?;
+        Ok(())
+    }
+
+    /// Record a download, replacing an older entry for the same file
+    pub fn add(&mut self, entry: Entry) {
+        self.entries.retain(|e| e.file != entry.file);
+        self.missing.retain(|e| e.file != entry.file);
+        self.entries.push(entry);
+        self.sort();
+    }
+
+    pub fn path(&self, entry: &Entry) -> PathBuf {
+        self.dir.join(&entry.file)
+    }
+
+    fn sort(&mut self) {
+        self.entries.sort_by(|a, b| {
+            let episode = |e: &Entry| e.episode.parse::<f64>().unwrap_or(f64::MAX);
+            a.show_name
+                .cmp(&b.show_name)
+                .then(a.show_id.cmp(&b.show_id))
+                .then(episode(a).total_cmp(&episode(b)))
+                .then(a.mode.cmp(&b.mode))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file: &str, show: &str, episode: &str) -> Entry {
+        Entry {
+            file: file.to_string(),
+            show_id: show.to_lowercase(),
+            show_name: show.to_string(),
+            episode: episode.to_string(),
+            mode: "sub".to_string(),
+            subtitles: Vec::new(),
+            duration_minutes: Some(24),
+            watched: false,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sho-library-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn groups_by_show_in_episode_order() {
+        let mut library = Library::default();
+        library.add(entry("b10.mp4", "Frieren", "10"));
+        library.add(entry("a2.mp4", "Dandadan", "2"));
+        library.add(entry("b9.mp4", "Frieren", "9"));
+        library.add(entry("a1.mp4", "Dandadan", "1"));
+
+        let files: Vec<_> = library.entries.iter().map(|e| e.file.as_str()).collect();
+        assert_eq!(files, ["a1.mp4", "a2.mp4", "b9.mp4", "b10.mp4"]);
+    }
+
+    #[test]
+    fn saves_and_hides_deleted_files() {
+        let dir = temp_dir("manifest");
+        fs::write(dir.join("kept.mp4"), b"").unwrap();
+
+        let mut library = Library::load(&dir).unwrap();
+        assert!(library.entries.is_empty());
+        library.add(entry("kept.mp4", "Frieren", "1"));
+        library.add(entry("deleted.mp4", "Frieren", "2"));
+        library.entries[0].watched = true;
+        library.save().unwrap();
+
+        let library = Library::load(&dir).unwrap();
+        assert_eq!(library.entries.len(), 1);
+        assert!(library.entries[0].watched);
+
+        // saving while it is hidden keeps it for when the file is back
+        library.save().unwrap();
+        fs::write(dir.join("deleted.mp4"), b"").unwrap();
+        let library = Library::load(&dir).unwrap();
+        assert_eq!(library.entries.len(), 2);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,34 +1,70 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::eyre;
 use nucleo_matcher::{
     Config, Matcher, Utf32Str,
     pattern::{AtomKind, CaseMatching, Normalization, Pattern},
 };
 use ratatui::{
     DefaultTerminal, Frame,
-    crossterm::event::{self, Event},
-    layout::{Constraint, HorizontalAlignment, Layout, Rect},
+    crossterm::{
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, MouseButton, MouseEvent,
+            MouseEventKind,
+        },
+        execute,
+    },
+    layout::{Constraint, HorizontalAlignment, Position, Rect},
     style::{Color, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Cell, Paragraph, Row, Table, TableState, Wrap},
+    widgets::{Block, BorderType, Cell, Paragraph, Row, Table, TableState, Tabs, Wrap},
 };
+use ratatui_image::{StatefulImage, picker::Picker, protocol::StatefulProtocol};
 use ratatui_macros::{horizontal, line, span, vertical};
+use serde::Serialize;
 use std::{
-    env,
-    ops::Index,
+    collections::{HashMap, HashSet},
+    env, fmt, fs,
     process::Command,
-    sync::{Arc, mpsc},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
     thread,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tui_input::{Input, backend::crossterm::EventHandler};
 
 mod api;
+mod config;
+mod extractors;
+mod library;
+mod player;
+mod schedule;
+mod template;
+mod tracker;
 mod utils;
+mod watchlist;
 use crate::{
-    api::{AnimeEdge, Api, Mode},
-    utils::decrypt_url,
+    api::{
+        AnimeEdge, Api, Browse, EPISODE_INFO_PAGE, EpisodeInfo, EpisodeList, FranchiseShow, Mode,
+        Probe, ProviderList, ShowDetail, preferred_subtitle,
+    },
+    library::Library,
+    player::Player,
+    schedule::Airing,
+    template::PlayerContext,
+    tracker::{History, Tracker, Watched},
+    utils::{count_matches, decrypt_url, file_name_part, find_episode, format_unix, url_extension},
+    watchlist::{Followed, Watchlist},
 };
 
+/// shows with more episodes than this get a grid instead of a row per episode
+const GRID_MIN_EPISODES: usize = 50;
+
+/// blank lines between a table header and the first row
+const HEADER_MARGIN: u16 = 1;
+
 #[derive(Parser, Debug)]
 struct Args {
     /// Name of the anime to watch
@@ -39,19 +75,219 @@ struct Args {
     #[arg(short, long, value_enum, default_value_t = Mode::Sub)]
     mode: Mode,
 
+    /// Catalogue to open on launch, popular is used when no name is given
+    #[arg(short, long, value_enum)]
+    browse: Option<Browse>,
+
+    /// Providers to play without asking, in order of preference (comma separated),
+    /// overrides `providers` of the config file
+    #[arg(long, value_delimiter = ',')]
+    provider: Vec<String>,
+
+    /// Player preset to launch, takes precedence over SHO_PLAYER_CMD
+    #[arg(long, value_enum)]
+    player: Option<Player>,
+
+    /// Open the downloaded episodes without going online
+    #[arg(short, long)]
+    library: bool,
+
     /// Enable debug output
     #[arg(long)]
     debug: bool,
+
+    #[command(subcommand)]
+    command: Option<SubCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum SubCommand {
+    /// Expected airing times of the shows on the watchlist
+    Schedule {
+        /// Print the schedule as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Look for new episodes of the shows on the watchlist
+    Check {
+        /// Print each show with new episodes as a line of json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Episode progress on AniList and MyAnimeList
+    Tracker {
+        #[command(subcommand)]
+        action: TrackerAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TrackerAction {
+    /// Push the episodes finished in sho to every tracker with a token in the config
+    Sync,
 }
 
 #[derive(Debug, Default)]
 struct Resp {
     search: Option<Vec<AnimeEdge>>,
-    episode_list: Option<(String, Vec<String>, String)>,
-    episode_provider_list: Option<(String, Vec<(String, String)>)>,
+    episode_list: Option<EpisodeList>,
+    episode_provider_list: Option<ProviderList>,
+    franchise: Option<Vec<FranchiseShow>>,
+    /// decoded image along with the url it was downloaded from
+    thumbnail: Option<(String, image::DynamicImage)>,
+    /// result of probing the provider link with this url
+    probe: Option<(String, Probe)>,
+    /// a later page of episode infos for the show with this id
+    episode_infos: Option<(String, HashMap<String, EpisodeInfo>)>,
+    schedule: Option<Vec<Airing>>,
+    /// mode the search results or episodes were fetched in
+    mode: Option<Mode>,
+    /// page of episode infos that could not be fetched, by show id and first episode
+    info_page_failed: Option<(String, String)>,
+    /// request that failed, and whether the api could not be reached at all
+    error: Option<(String, bool)>,
+    /// answer to a request made behind the current view, which it leaves alone
+    background: bool,
 }
 
-#[derive(Debug)]
+/// episode thumbnail ready to be drawn
+struct Thumbnail {
+    url: String,
+    protocol: StatefulProtocol,
+}
+
+impl fmt::Debug for Thumbnail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Thumbnail").field("url", &self.url).finish()
+    }
+}
+
+/// action of a clickable footer hint
+#[derive(Debug, Clone, Copy)]
+enum Hint {
+    Up,
+    Down,
+    Select,
+    Back,
+    Tab,
+}
+
+/// how a run of the player ended
+enum Launch {
+    /// the print preset wrote the command instead of running it
+    Printed,
+    Played {
+        elapsed: Duration,
+    },
+    /// the player could not be started or exited with an error, the time says nothing then
+    Failed,
+    /// the default downloader ran, `success` when it exited cleanly
+    Downloaded {
+        success: bool,
+    },
+}
+
+/// requests started by `spawn_request` that did not answer yet
+static PENDING: AtomicUsize = AtomicUsize::new(0);
+
+/// run an api call on a worker thread and hand its response to the ui loop,
+/// `background` for the ones that fill in the current view instead of changing it
+fn spawn_request<F>(
+    tx: &mpsc::Sender<Option<Resp>>,
+    context: &'static str,
+    background: bool,
+    job: F,
+) where
+    F: FnOnce() -> Result<Resp, Box<dyn std::error::Error>> + Send + 'static,
+{
+    let tx = tx.clone();
+    PENDING.fetch_add(1, Ordering::Relaxed);
+    thread::spawn(move || {
+        let mut resp = job().unwrap_or_else(|e| error_resp(context, e.as_ref()));
+        resp.background = background;
+        PENDING.fetch_sub(1, Ordering::Relaxed);
+        let _ = tx.send(Some(resp));
+    });
+}
+
+/// response for a failed request, logged with `context`
+fn error_resp(context: &str, e: &(dyn std::error::Error + 'static)) -> Resp {
+    eprintln!("{}: {}", context, e);
+    let unreachable = e.downcast_ref::<ureq::Error>().is_some_and(|e| {
+        matches!(
+            e,
+            ureq::Error::Io(_)
+                | ureq::Error::Timeout(_)
+                | ureq::Error::HostNotFound
+                | ureq::Error::ConnectionFailed
+        )
+    });
+    Resp {
+        error: Some((format!("{}: {}", context, e), unreachable)),
+        ..Default::default()
+    }
+}
+
+/// File the downloader saves the episode of `ctx` to, `<show>-<episode>-<mode>.<ext>`
+fn download_name(ctx: &PlayerContext) -> String {
+    let episode = ctx.episode.replace('.', "_");
+    format!(
+        "{}-{}-{}.{}",
+        file_name_part(&ctx.show_id, "show"),
+        file_name_part(&episode, "episode"),
+        file_name_part(&ctx.mode, "sub"),
+        file_name_part(url_extension(&ctx.url).unwrap_or_default(), "mp4")
+    )
+}
+
+/// data index of the `selected` row, none when the filter left no rows
+fn selected_index(rows: &[usize], selected: Option<usize>) -> Option<usize> {
+    selected.and_then(|row| rows.get(row)).copied()
+}
+
+/// char positions of `text` matched by `pattern`, in order
+fn match_indices(pattern: &Pattern, matcher: &mut Matcher, text: &str) -> Vec<u32> {
+    let mut buf = Vec::new();
+    let mut indices = Vec::new();
+    pattern.indices(Utf32Str::new(text, &mut buf), matcher, &mut indices);
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// indices matched in `first second` as ones of `first` and of `second`,
+/// leaving out the space between them, which `\ ` in the filter matches
+fn split_indices(indices: &[u32], first_len: u32) -> (Vec<u32>, Vec<u32>) {
+    let first = indices.iter().copied().filter(|i| *i < first_len).collect();
+    let second = indices
+        .iter()
+        .filter_map(|i| i.checked_sub(first_len + 1))
+        .collect();
+    (first, second)
+}
+
+/// `text` in `style` with the chars at `indices` highlighted
+fn highlight(text: &str, indices: &[u32], style: Style) -> Vec<Span<'static>> {
+    let matched = style.patch(Style::new().light_yellow().underlined());
+    let mut spans: Vec<Span> = Vec::new();
+    let mut run = String::new();
+    let mut in_match = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_match = indices.binary_search(&(i as u32)).is_ok();
+        if is_match != in_match && !run.is_empty() {
+            let style = if in_match { matched } else { style };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        in_match = is_match;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if in_match { matched } else { style }));
+    }
+    spans
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// View of the app
 enum View {
     /// show loading layout
@@ -62,6 +298,27 @@ enum View {
     Episode,
     /// select provider
     Provider,
+    /// select a show of the franchise
+    Related,
+    /// select a downloaded episode
+    Library,
+    /// select a followed show
+    Watchlist,
+    /// airing calendar of the followed shows
+    Schedule,
+}
+
+/// A view left for a deeper one, restored as it was by going back
+#[derive(Debug)]
+struct ViewState {
+    view: View,
+    input: Input,
+    rows_to_data_index: Vec<usize>,
+    selected: Option<usize>,
+    offset: usize,
+    /// replaced by jumping to a related show
+    episode_list: Option<EpisodeList>,
+    franchise: Option<Vec<FranchiseShow>>,
 }
 
 #[derive(Debug)]
@@ -72,39 +329,171 @@ struct App {
     view: View,
     /// condition that is allowing loop to contine
     exit: bool,
-    /// arguments passed to cli
-    args: Args,
     /// search bar input state
     input: Input,
+    /// translation requested from the api
+    mode: Mode,
+    /// search result id or episode to select once the next response arrives
+    reselect: Option<String>,
+    /// catalogue shown in the search view, `None` is the search tab
+    browse: Option<Browse>,
+    /// the search tab or a catalogue is loading, so its tabs stay on screen
+    searching: bool,
+    /// query sent to the api for the search tab
+    query: String,
+    /// queries searched remotely during this session, oldest first
+    query_history: Vec<String>,
+    /// position in `query_history` while recalling queries
+    history_cursor: Option<usize>,
     api: Arc<Api>,
     resp: Resp,
+    /// seconds to resume (show id, episode) from, guessed from time spent in the player
+    resume: HashMap<(String, String), u64>,
+    /// views left to reach the current one, the last one is restored by going back
+    nav_stack: Vec<ViewState>,
     matcher: Matcher,
+    /// picks the image protocol supported by the terminal
+    picker: Picker,
+    /// thumbnail of the selected episode
+    thumbnail: Option<Thumbnail>,
+    /// url of the last thumbnail that was requested
+    thumbnail_url: Option<String>,
+    /// thumbnail of the selection and since when it is selected
+    thumbnail_wanted: Option<(String, Instant)>,
+    /// pages of episode infos requested, by show id and first episode of the page
+    info_pages: HashSet<(String, String)>,
+    /// page whose infos could not be fetched, retried once the selection comes back to it
+    info_page_failed: Option<(String, String)>,
     rows_to_data_index: Vec<usize>,
     table_state: TableState,
     ui_loop_tick: Instant,
     selected_row: usize,
+    /// preset used instead of `SHO_PLAYER_CMD`
+    player: Option<Player>,
+    /// output of the print preset, shown once the tui is closed
+    printed: Option<String>,
+    /// providers played without going through the provider view, in order of preference
+    providers: Vec<String>,
+    /// show the provider view for the links being fetched even if a preferred one is there
+    pick_provider: bool,
+    /// liveness of the provider links by url, missing while the probe is running
+    probes: HashMap<String, Probe>,
+    /// episodes saved by the downloader
+    library: Library,
+    /// trackers that get the progress of finished episodes
+    trackers: Arc<Vec<Tracker>>,
+    history: History,
+    /// followed shows
+    watchlist: Watchlist,
+    /// where the table of the current view was drawn, for mouse clicks
+    table_area: Rect,
+    footer_area: Rect,
+    /// columns of the episode grid, 0 when the episodes are drawn as a table
+    grid_columns: usize,
+    /// row and time of the last click, to tell double clicks apart
+    last_click: Option<(usize, Instant)>,
+    /// the input holds an exact episode to jump to instead of a fuzzy filter
+    goto_episode: bool,
+    /// started with `--library`, or the last request could not reach the api
+    offline: bool,
+    /// the view shows what was kept when it was left, not a fresh response
+    cached: bool,
+    /// view replaced by `View::Loading`, put back when its request fails
+    before_loading: Option<ViewState>,
+    /// last request that failed, shown in the status bar
+    last_error: Option<String>,
+    /// the search filter also looks for its words in the descriptions
+    search_descriptions: bool,
 }
 
 impl App {
-    fn new() -> Self {
-        let args = Args::parse();
-        let mode = args.mode;
-        let api = Arc::new(Api::new(mode, args.debug));
+    fn new(args: Args) -> color_eyre::eyre::Result<Self> {
+        if let Some(binary) = args.player.and_then(|p| p.binary())
+            && player::find_in_path(binary).is_none()
+        {
+            return Err(eyre!(
+                "player '{}' was not found in PATH, install it or pick another with --player",
+                binary
+            ));
+        }
 
-        Self {
+        let config = config::Config::load().map_err(|e| eyre!("{}", e))?;
+        let trackers = Arc::new(Tracker::from_config(&config));
+        let history = History::load(History::path()).map_err(|e| eyre!("{}", e))?;
+        let watchlist = Watchlist::load(Watchlist::path()).map_err(|e| eyre!("{}", e))?;
+        let library = match Library::default_dir(config.download_dir) {
+            Some(dir) => Library::load(&dir).map_err(|e| eyre!("{}", e))?,
+            None => Library::default(),
+        };
+        let providers = if args.provider.is_empty() {
+            config.providers
+        } else {
+            args.provider.clone()
+        };
+
+        let api = Arc::new(Api::new(args.debug));
+        let browse = match (&args.name, args.browse) {
+            (None, None) => Some(Browse::Popular),
+            (_, browse) => browse,
+        };
+        let query = args.name.clone().unwrap_or_default();
+        let query_history = args.name.iter().cloned().collect();
+
+        Ok(Self {
             select_icon: String::default(),
             table_state: TableState::default(),
-            args,
             input: Input::default(),
+            mode: args.mode,
+            reselect: None,
+            browse,
+            searching: false,
+            query,
+            query_history,
+            history_cursor: None,
             api,
             matcher: Matcher::new(Config::DEFAULT),
-            rows_to_data_index: Vec::new(),
+            picker: Picker::halfblocks(),
+            thumbnail: None,
+            thumbnail_url: None,
+            thumbnail_wanted: None,
+            info_pages: HashSet::new(),
+            info_page_failed: None,
             exit: false,
-            view: View::Loading,
             resp: Resp::default(),
+            resume: HashMap::new(),
+            nav_stack: Vec::new(),
             ui_loop_tick: Instant::now(),
             selected_row: 0,
-        }
+            player: args.player,
+            printed: None,
+            providers,
+            pick_provider: false,
+            probes: HashMap::new(),
+            rows_to_data_index: if args.library {
+                (0..library.entries.len()).collect()
+            } else {
+                Vec::new()
+            },
+            view: if args.library {
+                View::Library
+            } else {
+                View::Loading
+            },
+            library,
+            trackers,
+            history,
+            watchlist,
+            table_area: Rect::default(),
+            footer_area: Rect::default(),
+            grid_columns: 0,
+            last_click: None,
+            goto_episode: false,
+            offline: args.library,
+            cached: false,
+            before_loading: None,
+            last_error: None,
+            search_descriptions: false,
+        })
     }
 
     fn select_icon_animation(&mut self) {
@@ -123,336 +512,2219 @@ impl App {
         }
     }
 
-    fn main_loop(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
-        let (tx, rx) = mpsc::channel::<Option<Resp>>();
+    /// fetch the results of the active search tab
+    fn request_search(&mut self, tx: &mpsc::Sender<Option<Resp>>, mode: Mode) {
+        self.searching = true;
+        let api = self.api.clone();
+        let name = self.query.clone();
+        let browse = self.browse;
 
-        let api_clone = self.api.clone();
-        let name = self.args.name.clone();
-        let tx_clone = tx.clone();
-        thread::spawn(
-            move || match api_clone.search_anime(name.unwrap_or_default().as_str()) {
-                Ok(resp) => tx_clone.send(Some(Resp {
-                    search: Some(resp.data.shows.edges),
-                    ..Default::default()
-                })),
-                Err(e) => {
-                    eprintln!("Error getting search results: {}", e);
-                    tx_clone.send(None)
-                }
-            },
-        );
+        spawn_request(tx, "Error getting search results", false, move || {
+            let search = match browse {
+                Some(browse) => api.browse_anime(browse, mode)?,
+                None => api.search_anime(&name, mode)?.data.shows.edges,
+            };
+            Ok(Resp {
+                search: Some(search),
+                mode: Some(mode),
+                ..Default::default()
+            })
+        });
+    }
 
-        while !self.exit {
-            if let Ok(Some(resp)) = rx.try_recv() {
-                if let Some(search_resp) = resp.search {
-                    self.rows_to_data_index = (0..search_resp.len()).collect();
-                    self.resp.search = Some(search_resp);
-                    self.view = View::Search
-                }
-                if let Some(ep_list_resp) = resp.episode_list {
-                    self.rows_to_data_index = (0..ep_list_resp.1.len()).collect();
-                    self.resp.episode_list = Some(ep_list_resp);
-                    self.view = View::Episode
+    /// fetch the episodes of the show with `id` in `mode`
+    fn request_episode_list(&self, tx: &mpsc::Sender<Option<Resp>>, id: String, mode: Mode) {
+        let api = self.api.clone();
+
+        spawn_request(tx, "Error getting episode list", false, move || {
+            Ok(Resp {
+                episode_list: Some(api.get_episode_list(&id, mode)?),
+                mode: Some(mode),
+                ..Default::default()
+            })
+        });
+    }
+
+    /// re-fetch the current view in the next mode, which is kept once it arrives
+    fn cycle_mode(&mut self, tx: &mpsc::Sender<Option<Resp>>) {
+        let mode = self.mode.next();
+
+        let selected = self
+            .table_state
+            .selected()
+            .and_then(|row| self.rows_to_data_index.get(row).copied());
+
+        match self.view {
+            View::Search => {
+                if let (Some(search), Some(index)) = (&self.resp.search, selected) {
+                    self.reselect = Some(search[index].id.clone());
                 }
-                if let Some(ep_provider_list_resp) = resp.episode_provider_list {
-                    self.rows_to_data_index = (0..ep_provider_list_resp.1.len()).collect();
-                    self.resp.episode_provider_list = Some(ep_provider_list_resp);
-                    self.view = View::Provider
+                self.request_search(tx, mode);
+            }
+            View::Episode => {
+                if let Some((show, list)) = &self.resp.episode_list {
+                    self.reselect = selected.map(|index| list[index].clone());
+                    self.request_episode_list(tx, show.id.clone(), mode);
                 }
+            }
+            _ => (),
+        }
+    }
+
+    /// row of the search result or episode saved in `reselect`
+    fn reselect_row(&mut self) -> Option<usize> {
+        let key = self.reselect.take()?;
+        match self.view {
+            View::Search => self.resp.search.as_ref()?.iter().position(|s| s.id == key),
+            View::Episode => self
+                .resp
+                .episode_list
+                .as_ref()?
+                .1
+                .iter()
+                .position(|e| *e == key),
+            _ => None,
+        }
+    }
+
+    /// move to the next or previous search tab and fetch its results
+    fn cycle_search_tab(&mut self, tx: &mpsc::Sender<Option<Resp>>, forward: bool) {
+        let tabs: Vec<Option<Browse>> = std::iter::once(None)
+            .chain(Browse::ALL.into_iter().map(Some))
+            .collect();
+        let current = tabs.iter().position(|t| *t == self.browse).unwrap_or(0);
+        let next = if forward {
+            (current + 1) % tabs.len()
+        } else {
+            (current + tabs.len() - 1) % tabs.len()
+        };
+
+        self.browse = tabs[next];
+        self.start_loading();
+        self.request_search(tx, self.mode);
+    }
 
-                self.table_state.select(Some(0));
+    /// search the api with the text in the search bar, replacing the results
+    fn remote_search(&mut self, tx: &mpsc::Sender<Option<Resp>>) {
+        let query = self.input.value().trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+
+        self.query_history.retain(|q| *q != query);
+        self.query_history.push(query.clone());
+        self.history_cursor = None;
+
+        self.query = query;
+        self.browse = None;
+        self.start_loading();
+        self.request_search(tx, self.mode);
+    }
+
+    /// put an older or newer query from the session history in the search bar
+    fn recall_query(&mut self, older: bool) {
+        if self.query_history.is_empty() {
+            return;
+        }
+
+        let last = self.query_history.len() - 1;
+        let cursor = match (self.history_cursor, older) {
+            (None, true) => last,
+            (None, false) => return,
+            (Some(i), true) => i.saturating_sub(1),
+            (Some(i), false) if i < last => i + 1,
+            (Some(_), false) => {
+                self.history_cursor = None;
                 self.input.reset();
+                self.update_row_to_data_index();
+                return;
             }
+        };
 
-            // match self.view {
-            //     View::Loading => (),
-            //     View::Search => {}
-            //     View::Episode => {}
-            //     View::Provider => {}
-            // }
+        self.history_cursor = Some(cursor);
+        self.input = Input::new(self.query_history[cursor].clone());
+        self.update_row_to_data_index();
+        self.select_first();
+    }
 
-            terminal.draw(|frame| self.render(frame))?;
+    /// download the thumbnail of the selected episode once the selection settles on it
+    fn request_thumbnail(&mut self, tx: &mpsc::Sender<Option<Resp>>) {
+        let url = match (&self.view, &self.resp.episode_list) {
+            (View::Episode, Some((show, list))) => self
+                .table_state
+                .selected()
+                .and_then(|row| self.rows_to_data_index.get(row))
+                .and_then(|index| show.episode_infos.get(&list[*index]))
+                .and_then(|info| info.thumbnail_url()),
+            _ => None,
+        };
 
-            self.select_icon_animation();
+        let Some(url) = url else {
+            return;
+        };
+        if Some(&url) == self.thumbnail_url.as_ref() {
+            return;
+        }
+        // scrolling through the list should not download every thumbnail on the way
+        match &self.thumbnail_wanted {
+            Some((wanted, since)) if *wanted == url => {
+                if since.elapsed() < Duration::from_millis(250) {
+                    return;
+                }
+            }
+            _ => {
+                self.thumbnail_wanted = Some((url, Instant::now()));
+                return;
+            }
+        }
+        self.thumbnail_url = Some(url.clone());
 
-            if event::poll(Duration::from_millis(16))? {
-                let event = event::read()?;
-                if let Event::Key(key) = event {
-                    match key.code {
-                        event::KeyCode::Esc => return Ok(()),
-                        event::KeyCode::Char('q')
-                            if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
-                        {
-                            return Ok(());
-                        }
-                        event::KeyCode::Down => {
-                            self.table_state.select_next();
-                            if let Some(row) = self.table_state.selected() {
-                                self.selected_row = row
-                            }
-                        }
-                        event::KeyCode::Char('j') | event::KeyCode::Char('n')
-                            if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
-                        {
-                            self.table_state.select_next();
-                            if let Some(row) = self.table_state.selected() {
-                                self.selected_row = row
-                            }
-                        }
-                        event::KeyCode::Up => {
-                            self.table_state.select_previous();
-                            if let Some(row) = self.table_state.selected() {
-                                self.selected_row = row
-                            }
-                        }
-                        event::KeyCode::Char('k') | event::KeyCode::Char('p')
-                            if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
-                        {
-                            self.table_state.select_previous();
-                            if let Some(row) = self.table_state.selected() {
-                                self.selected_row = row
-                            }
-                        }
-                        event::KeyCode::Backspace | event::KeyCode::Char('h')
-                            if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
-                        {
-                            match self.view {
-                                View::Loading => (),
-                                View::Search => return Ok(()),
-                                View::Episode => self.view = View::Search,
-                                View::Provider => self.view = View::Episode,
-                            }
-                            self.input.reset();
-                            self.table_state.select(Some(0));
-                            self.update_row_to_data_index()
-                        }
-                        event::KeyCode::Enter => match self.view {
-                            View::Loading => (),
-                            View::Search => {
-                                if let Some(resp) = &self.resp.search {
-                                    let Some(row) = self.table_state.selected() else {
-                                        return Ok(());
-                                    };
-                                    let id = resp[self.rows_to_data_index[row]].id.clone();
-
-                                    let tx_clone = tx.clone();
-                                    let api_clone = self.api.clone();
-                                    thread::spawn(move || match api_clone.get_episode_list(&id) {
-                                        Ok(resp) => tx_clone.send(Some(Resp {
-                                            episode_list: Some(resp),
-                                            ..Default::default()
-                                        })),
-                                        Err(e) => {
-                                            eprintln!("Error getting episode list: {}", e);
-                                            tx_clone.send(None)
-                                        }
-                                    });
-                                }
-                            }
-                            View::Episode => {
-                                if let Some((_, list, id)) = &self.resp.episode_list {
-                                    let Some(row) = self.table_state.selected() else {
-                                        return Ok(());
-                                    };
-                                    let ep = list[self.rows_to_data_index[row]].clone();
-                                    let id_clone = id.clone();
-                                    let tx_clone = tx.clone();
-                                    let api_clone = self.api.clone();
-                                    thread::spawn(move || {
-                                        match api_clone.get_episode_links(&id_clone, &ep) {
-                                            Ok(resp) => tx_clone.send(Some(Resp {
-                                                episode_provider_list: Some(resp),
-                                                ..Default::default()
-                                            })),
-                                            Err(e) => {
-                                                eprintln!("Error getting episode links: {}", e);
-                                                tx_clone.send(None)
-                                            }
-                                        }
-                                    });
-                                }
-                            }
-                            View::Provider => {
-                                if let Some((_, links)) = &self.resp.episode_provider_list {
-                                    let Some(row) = self.table_state.selected() else {
-                                        return Ok(());
-                                    };
-                                    let (_provider, url) = &links[self.rows_to_data_index[row]];
-                                    let api = self.api.clone();
-
-                                    let url = if url.contains("clock.json")
-                                        || url.contains("https://allanime.day")
-                                    {
-                                        api.resolve_clock_urls(url).unwrap()
-                                    } else {
-                                        url.to_string()
-                                    };
-
-                                    let mut player_cmd =
-                                        env::var("SHO_PLAYER_CMD").unwrap_or(
-                                        "curl -L -H 'Referer: {referer}' -H 'User-Agent: {user_agent}' {url} -O --progress-bar".to_string());
-
-                                    if player_cmd.contains("{url}") {
-                                        player_cmd = player_cmd.replace("{url}", &url);
-                                    }
-                                    if player_cmd.contains("{referer}") {
-                                        player_cmd = player_cmd.replace("{referer}", api.referer)
-                                    }
-                                    if player_cmd.contains("{user_agent}") {
-                                        player_cmd =
-                                            player_cmd.replace("{user_agent}", api.user_agent)
-                                    }
-
-                                    // windows
-                                    #[cfg(not(unix))]
-                                    let (shell, flag) = ("cmd", "/C");
-
-                                    #[cfg(unix)]
-                                    let (shell, flag) = ("sh", "-c");
-
-                                    let cmd = Command::new(shell)
-                                        .arg(flag)
-                                        .arg(player_cmd)
-                                        .status()
-                                        .expect("Failed to execute curl")
-                                        .code()
-                                        .unwrap_or(1);
-
-                                    if cmd == 1 {
-                                        self.exit = true;
-                                    }
-                                    self.view = View::Episode
-                                }
-                            }
-                        },
-                        _ => {
-                            self.input.handle_event(&event);
-                            self.update_row_to_data_index();
-                            self.table_state.select(Some(0));
-                        }
+        let api = self.api.clone();
+        spawn_request(tx, "Error getting thumbnail", true, move || {
+            let image = image::load_from_memory(&api.get_image(&url)?)?;
+            Ok(Resp {
+                thumbnail: Some((url, image)),
+                ..Default::default()
+            })
+        });
+    }
+
+    /// fetch the infos of the page of episodes the selection moved into
+    fn request_episode_infos(&mut self, tx: &mpsc::Sender<Option<Resp>>) {
+        let (View::Episode, Some((show, list))) = (&self.view, &self.resp.episode_list) else {
+            return;
+        };
+        let Some(index) = self
+            .table_state
+            .selected()
+            .and_then(|row| self.rows_to_data_index.get(row))
+        else {
+            return;
+        };
+
+        let start = index / EPISODE_INFO_PAGE * EPISODE_INFO_PAGE;
+        let page = &list[start..list.len().min(start + EPISODE_INFO_PAGE)];
+        let key = (show.id.clone(), page[0].clone());
+        // not again while the selection stays in the page that failed
+        if self.info_page_failed.as_ref() == Some(&key) {
+            return;
+        }
+        self.info_page_failed = None;
+        if !self.info_pages.insert(key.clone()) {
+            return;
+        }
+
+        let api = self.api.clone();
+        let last = page[page.len() - 1].clone();
+        spawn_request(tx, "Error getting episode infos", true, move || {
+            let (id, first) = &key;
+            match api.get_episode_infos(id, first, &last) {
+                Ok(infos) => Ok(Resp {
+                    episode_infos: Some((key.0, infos)),
+                    ..Default::default()
+                }),
+                Err(e) => Ok(Resp {
+                    info_page_failed: Some(key),
+                    ..error_resp("Error getting episode infos", e.as_ref())
+                }),
+            }
+        });
+    }
+
+    /// resolve `url` and hand it to the player, false when it could not play it
+    fn play(&mut self, episode: &str, provider: &str, url: &str) -> bool {
+        let api = self.api.clone();
+
+        let link = match api.resolve_link(provider, url) {
+            Ok(link) => link,
+            Err(e) => {
+                eprintln!("Error resolving {}: {}", provider, e);
+                return false;
+            }
+        };
+
+        let show = self.resp.episode_list.as_ref().map(|(show, _)| show);
+        let show_id = show.map(|s| s.id.clone()).unwrap_or_default();
+        let resume_key = (show_id.clone(), episode.to_string());
+        let start = self.resume.get(&resume_key).copied().unwrap_or(0);
+        let duration = show.and_then(|s| {
+            s.episode_infos
+                .get(episode)
+                .and_then(|info| info.duration_minutes())
+                .or(s.duration_minutes())
+        });
+        // kept even when trackers can't count it, the grid still marks it
+        let watched = show.map(|s| Watched {
+            name: s.name.clone(),
+            anilist_id: s.anilist_id(),
+            mal_id: s.mal_id(),
+            episode: tracker::episode_number(episode).unwrap_or(0),
+            finished: vec![episode.to_string()],
+        });
+
+        let subtitle_lang = env::var("SHO_SUBTITLE_LANG").unwrap_or("en".to_string());
+        let ctx = PlayerContext {
+            url: link.url.clone(),
+            referer: link.header("Referer").unwrap_or(api.referer).to_string(),
+            user_agent: link
+                .header("User-Agent")
+                .unwrap_or(api.user_agent)
+                .to_string(),
+            subtitle: preferred_subtitle(&link.subtitles, &subtitle_lang)
+                .map(|s| s.src.clone())
+                .unwrap_or_default(),
+            title: show.map(|s| s.name.clone()).unwrap_or_default(),
+            show_id,
+            episode: episode.to_string(),
+            mode: self.mode.as_str().to_string(),
+            provider: provider.to_string(),
+            quality: link.quality.clone().unwrap_or_default(),
+            start,
+        };
+
+        match self.launch(&ctx, false) {
+            // the next provider may work
+            None | Some(Launch::Failed) => return false,
+            Some(Launch::Printed) => (),
+            Some(Launch::Played { elapsed }) => {
+                let show_id = resume_key.0.clone();
+                if self.remember_position(resume_key, start + elapsed.as_secs(), duration)
+                    && let Some(watched) = watched
+                {
+                    self.finished(&show_id, watched, true);
+                }
+            }
+            Some(Launch::Downloaded { success: true }) => {
+                let file = download_name(&ctx);
+                let stem = file
+                    .rsplit_once('.')
+                    .map_or(file.as_str(), |(stem, _)| stem);
+
+                // the default downloader saves subtitles next to the video
+                let mut subtitles = Vec::new();
+                for subtitle in &link.subtitles {
+                    // both come from the api and end up in a path
+                    let ext = url_extension(&subtitle.src).unwrap_or_default();
+                    let name = format!(
+                        "{}.{}.{}",
+                        stem,
+                        file_name_part(&subtitle.lang, "und"),
+                        file_name_part(ext, "vtt")
+                    );
+                    match api.download_subtitle(subtitle, &self.library.dir.join(&name)) {
+                        Ok(()) => subtitles.push(name),
+                        Err(e) => eprintln!("Error downloading subtitle: {}", e),
                     }
                 }
+
+                self.library.add(library::Entry {
+                    file,
+                    show_id: ctx.show_id,
+                    show_name: ctx.title,
+                    episode: ctx.episode,
+                    mode: ctx.mode,
+                    subtitles,
+                    duration_minutes: duration,
+                    watched: false,
+                });
+                if let Err(e) = self.library.save() {
+                    eprintln!("Error saving library: {}", e);
+                }
             }
+            // nothing of a failed download goes into the library
+            Some(Launch::Downloaded { success: false }) => return false,
         }
-        Ok(())
+
+        true
     }
 
-    fn fuzzy_reorder(&mut self, str_vec: Vec<String>, buff: &mut Vec<char>) {
-        let pattern = Pattern::new(
-            self.input.value(),
-            CaseMatching::Smart,
-            Normalization::Smart,
-            AtomKind::Fuzzy,
-        );
+    /// play a downloaded episode of the library, false when the player could not be started
+    fn play_downloaded(&mut self, index: usize) -> bool {
+        let entry = self.library.entries[index].clone();
+        let resume_key = (entry.show_id.clone(), entry.episode.clone());
+        let start = self.resume.get(&resume_key).copied().unwrap_or(0);
 
-        let mut vec: Vec<(usize, u32)> = Vec::new();
-        for (i, e_str) in str_vec.iter().enumerate() {
-            if let Some(score) = pattern.score(Utf32Str::new(e_str, buff), &mut self.matcher) {
-                vec.push((i, score));
-            };
+        let subtitle_lang = env::var("SHO_SUBTITLE_LANG").unwrap_or("en".to_string());
+        let subtitle = entry
+            .subtitles
+            .iter()
+            .find(|name| name.contains(&format!(".{}.", subtitle_lang)))
+            .or(entry.subtitles.first())
+            .map(|name| self.library.dir.join(name).to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let ctx = PlayerContext {
+            url: self.library.path(&entry).to_string_lossy().to_string(),
+            referer: self.api.referer.to_string(),
+            user_agent: self.api.user_agent.to_string(),
+            subtitle,
+            title: entry.show_name.clone(),
+            show_id: entry.show_id.clone(),
+            episode: entry.episode.clone(),
+            mode: entry.mode.clone(),
+            provider: "local".to_string(),
+            quality: String::new(),
+            start,
+        };
+
+        match self.launch(&ctx, true) {
+            None => return false,
+            Some(Launch::Played { elapsed }) => {
+                let position = start + elapsed.as_secs();
+                if self.remember_position(resume_key, position, entry.duration_minutes) {
+                    self.library.entries[index].watched = true;
+                    if let Err(e) = self.library.save() {
+                        eprintln!("Error saving library: {}", e);
+                    }
+                    // no network here, `sho tracker sync` pushes it later
+                    let watched = Watched {
+                        name: entry.show_name.clone(),
+                        episode: tracker::episode_number(&entry.episode).unwrap_or(0),
+                        finished: vec![entry.episode.clone()],
+                        ..Default::default()
+                    };
+                    self.finished(&entry.show_id, watched, false);
+                }
+            }
+            Some(_) => (),
         }
 
-        vec.sort_by(|a, b| b.1.cmp(&a.1));
-        self.rows_to_data_index = vec.into_iter().map(|(i, _)| i).collect()
+        true
     }
 
-    /// update the index of rows to data pointer vec
-    fn update_row_to_data_index(&mut self) {
-        let mut buf = Vec::new();
+    /// record a finished episode and push it to the trackers in the background
+    fn finished(&mut self, show_id: &str, watched: Watched, push: bool) {
+        let show = self.history.record(show_id, watched).clone();
+        if let Err(e) = self.history.save() {
+            eprintln!("Error saving progress: {}", e);
+        }
+        if !push || self.trackers.is_empty() {
+            return;
+        }
 
-        match self.view {
-            View::Loading => (),
-            View::Search => {
-                if let Some(resp) = &self.resp.search {
-                    self.fuzzy_reorder(
-                        resp.iter()
-                            .map(|item| {
-                                if let Some(english_name) = &item.english_name {
-                                    format!("{} {}", item.name, english_name)
-                                } else {
-                                    item.name.to_string()
-                                }
-                            })
-                            .collect(),
-                        &mut buf,
-                    )
+        let trackers = self.trackers.clone();
+        thread::spawn(move || {
+            for (tracker, result) in tracker::push(&trackers, &show) {
+                if let Err(e) = result {
+                    eprintln!("Error updating {}: {}", tracker.service.name(), e);
                 }
             }
+        });
+    }
 
-            View::Episode => {
-                if let Some((_, resp, _)) = &self.resp.episode_list {
-                    self.fuzzy_reorder(resp.iter().map(|item| item.to_string()).collect(), &mut buf)
-                }
+    /// keep the position to resume from unless the episode was nearly over, true when it was.
+    /// Without a duration there is no telling, so it is never counted as finished
+    fn remember_position(
+        &mut self,
+        key: (String, String),
+        position: u64,
+        duration_minutes: Option<u64>,
+    ) -> bool {
+        match duration_minutes {
+            Some(minutes) if position >= minutes * 60 * 9 / 10 => {
+                self.resume.remove(&key);
+                true
+            }
+            _ => {
+                self.resume.insert(key, position);
+                false
             }
+        }
+    }
 
-            View::Provider => {
-                if let Some((_, resp)) = &self.resp.episode_provider_list {
-                    self.fuzzy_reorder(
-                        resp.iter().map(|item| item.0.to_string()).collect(),
-                        &mut buf,
-                    )
+    /// run the player on `ctx`, `None` when nothing could be started.
+    /// `local` files are never handed to the downloader
+    fn launch(&mut self, ctx: &PlayerContext, local: bool) -> Option<Launch> {
+        let custom_cmd = env::var("SHO_PLAYER_CMD").ok();
+        let downloader = self.player.is_none() && custom_cmd.is_none();
+        if downloader && local {
+            eprintln!("Pick a player with --player or SHO_PLAYER_CMD to play downloads");
+            return None;
+        }
+        let player_cmd = match (self.player, &custom_cmd) {
+            (Some(player), _) => player.template(!ctx.subtitle.is_empty()),
+            (None, Some(cmd)) => cmd.clone(),
+            (None, None) => format!(
+                "curl -L -H 'Referer: {{referer}}' -H 'User-Agent: {{user_agent}}' -o {} --progress-bar -- {{url}}",
+                download_name(ctx)
+            ),
+        };
+        let player_cmd = player_cmd.as_str();
+        // players without `--` would take it as an option
+        if ctx.url.starts_with('-') {
+            eprintln!("Refusing to play {}, it looks like an option", ctx.url);
+            return None;
+        }
+
+        if self.player == Some(Player::Print) {
+            self.printed = Some(template::render(player_cmd, ctx));
+            self.exit = true;
+            return Some(Launch::Printed);
+        }
+
+        let mut command =
+            if self.player.is_none() && env::var("SHO_PLAYER_SHELL").is_ok_and(|v| v == "1") {
+                // opt-in: values from the api end up in a shell command line
+                #[cfg(not(unix))]
+                let (shell, flag) = ("cmd", "/C");
+
+                #[cfg(unix)]
+                let (shell, flag) = ("sh", "-c");
+
+                let mut command = Command::new(shell);
+                command.arg(flag).arg(template::render(player_cmd, ctx));
+                command
+            } else {
+                let argv = match template::render_args(player_cmd, ctx) {
+                    Ok(argv) if !argv.is_empty() => argv,
+                    Ok(_) => {
+                        eprintln!("SHO_PLAYER_CMD is empty");
+                        return None;
+                    }
+                    Err(e) => {
+                        eprintln!("Error parsing SHO_PLAYER_CMD: {}", e);
+                        return None;
+                    }
+                };
+                let mut command = Command::new(&argv[0]);
+                command.args(&argv[1..]);
+                command
+            };
+
+        let dir = &self.library.dir;
+        if downloader && !dir.as_os_str().is_empty() {
+            match fs::create_dir_all(dir) {
+                Ok(()) => {
+                    command.current_dir(dir);
                 }
+                Err(e) => eprintln!("Error creating {}: {}", dir.display(), e),
             }
         }
-    }
 
-    fn render_search_input(&self, frame: &mut Frame, area: Rect) {
-        frame.render_widget(
-            Paragraph::new(self.input.value())
-                .alignment(HorizontalAlignment::Center)
-                .block(
-                    Block::bordered()
-                        .title("Fuzzy Search")
-                        .title_style(Style::new().green().bold())
-                        .style(Style::new().red())
-                        .border_type(BorderType::Rounded),
-                ),
+        let program = command.get_program().to_string_lossy().to_string();
+        let started = Instant::now();
+        let status = match command.status() {
+            Ok(status) => status,
+            Err(e) => {
+                let error = format!("Error launching {}: {}", program, e);
+                eprintln!("{}", error);
+                self.last_error = Some(error);
+                return Some(Launch::Failed);
+            }
+        };
+        if !status.success() {
+            self.last_error = Some(format!("{} exited with {}", program, status));
+        }
+
+        Some(if downloader {
+            Launch::Downloaded {
+                success: status.success(),
+            }
+        } else if !status.success() {
+            Launch::Failed
+        } else {
+            // sho can't ask the player where it stopped, time spent in it is the best guess
+            Launch::Played {
+                elapsed: started.elapsed(),
+            }
+        })
+    }
+
+    /// play the first preferred provider of `links` the player manages to play
+    fn autoplay(&mut self, links: &ProviderList) -> bool {
+        let (episode, links) = links;
+        for wanted in self.providers.clone() {
+            let candidate = links
+                .iter()
+                .find(|(provider, _)| provider.eq_ignore_ascii_case(&wanted));
+            if let Some((provider, url)) = candidate
+                && self.play(episode, provider, url)
+            {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// fetch the links of the selected episode
+    fn request_episode_links(&mut self, tx: &mpsc::Sender<Option<Resp>>, pick_provider: bool) {
+        let Some((show, list)) = &self.resp.episode_list else {
+            return;
+        };
+        let Some(index) = selected_index(&self.rows_to_data_index, self.table_state.selected())
+        else {
+            return;
+        };
+
+        let ep = list[index].clone();
+        let id = show.id.clone();
+        let api = self.api.clone();
+        let mode = self.mode;
+        self.pick_provider = pick_provider;
+
+        spawn_request(tx, "Error getting episode links", false, move || {
+            Ok(Resp {
+                episode_provider_list: Some(api.get_episode_links(&id, &ep, mode)?),
+                ..Default::default()
+            })
+        });
+    }
+
+    /// re-read the watchlist, `sho check` may have found new episodes since it was loaded
+    fn reload_watchlist(&mut self) {
+        match Watchlist::load(Watchlist::path()) {
+            Ok(watchlist) => self.watchlist = watchlist,
+            Err(e) => eprintln!("Error loading watchlist: {}", e),
+        }
+    }
+
+    /// follow the shown show in the current mode, or stop following it
+    fn toggle_follow(&mut self) {
+        let Some((show, episodes)) = self.resp.episode_list.clone() else {
+            return;
+        };
+        self.reload_watchlist();
+        self.watchlist.toggle(
+            &show.id,
+            Followed {
+                name: show.name,
+                mode: self.mode,
+                episodes,
+                unseen: Vec::new(),
+            },
+        );
+        if let Err(e) = self.watchlist.save() {
+            eprintln!("Error saving watchlist: {}", e);
+        }
+    }
+
+    /// fetch when the followed shows air next
+    fn request_schedule(&mut self, tx: &mpsc::Sender<Option<Resp>>) {
+        let followed = self.watchlist.shows.clone();
+        let api = self.api.clone();
+        self.start_loading();
+
+        spawn_request(tx, "Error getting schedule", false, move || {
+            let ids: Vec<String> = followed.keys().cloned().collect();
+            let shows = api.get_airing(&ids)?;
+            Ok(Resp {
+                schedule: Some(schedule::airings(&followed, &shows, unix_now())),
+                ..Default::default()
+            })
+        });
+    }
+
+    /// the episodes of a followed show were looked at, so they are not new anymore
+    fn saw_episodes(&mut self, (show, episodes): &EpisodeList) {
+        if self
+            .watchlist
+            .shows
+            .get(&show.id)
+            .is_none_or(|followed| followed.mode != self.mode)
+        {
+            return;
+        }
+        self.reload_watchlist();
+        self.watchlist.update(&show.id, episodes);
+        self.watchlist.mark_seen(&show.id);
+        if let Err(e) = self.watchlist.save() {
+            eprintln!("Error saving watchlist: {}", e);
+        }
+    }
+
+    /// list the downloaded episodes, re-reading the manifest
+    fn open_library(&mut self) {
+        let dir = self.library.dir.clone();
+        match Library::load(&dir) {
+            Ok(library) => self.library = library,
+            Err(e) => eprintln!("Error loading library: {}", e),
+        }
+        self.enter(View::Library);
+        self.input.reset();
+        self.rows_to_data_index = (0..self.library.entries.len()).collect();
+        self.table_state.select(Some(0));
+        self.selected_row = 0;
+    }
+
+    fn toggle_watched(&mut self) {
+        let Some(index) = self
+            .table_state
+            .selected()
+            .and_then(|row| self.rows_to_data_index.get(row))
+        else {
+            return;
+        };
+        let entry = &mut self.library.entries[*index];
+        entry.watched = !entry.watched;
+        if let Err(e) = self.library.save() {
+            eprintln!("Error saving library: {}", e);
+        }
+    }
+
+    /// check every provider link in parallel
+    fn request_probes(&mut self, tx: &mpsc::Sender<Option<Resp>>) {
+        let Some((_, links)) = &self.resp.episode_provider_list else {
+            return;
+        };
+        self.probes.clear();
+
+        for (provider, url) in links {
+            let api = self.api.clone();
+            let (provider, url) = (provider.clone(), url.clone());
+            spawn_request(tx, "Error probing link", true, move || {
+                let probe = api.probe_link(&provider, &url);
+                Ok(Resp {
+                    probe: Some((url, probe)),
+                    ..Default::default()
+                })
+            });
+        }
+    }
+
+    /// go back to the previous view, false when there is nothing left to go back to
+    fn back(&mut self, tx: &mpsc::Sender<Option<Resp>>) -> bool {
+        self.goto_episode = false;
+        if let Some(state) = self.nav_stack.pop() {
+            self.restore(state);
+            return true;
+        }
+
+        match self.view {
+            View::Loading => (),
+            View::Search => return false,
+            // opened with --library, nothing was searched yet
+            _ if self.resp.search.is_none() => {
+                self.start_loading();
+                self.request_search(tx, self.mode)
+            }
+            _ => self.view = View::Search,
+        }
+        self.input.reset();
+        self.table_state.select(Some(0));
+        self.update_row_to_data_index();
+        true
+    }
+
+    fn snapshot(&self) -> ViewState {
+        ViewState {
+            view: self.view,
+            input: self.input.clone(),
+            rows_to_data_index: self.rows_to_data_index.clone(),
+            selected: self.table_state.selected(),
+            offset: self.table_state.offset(),
+            episode_list: self.resp.episode_list.clone(),
+            franchise: self.resp.franchise.clone(),
+        }
+    }
+
+    /// switch to `view`, remembering the current one to go back to
+    fn enter(&mut self, view: View) {
+        if self.view != view && self.view != View::Loading {
+            self.nav_stack.push(self.snapshot());
+        }
+        self.view = view;
+        self.cached = false;
+    }
+
+    /// show the skeleton until a request answers, keeping the current view in case it fails
+    fn start_loading(&mut self) {
+        if self.view != View::Loading {
+            self.before_loading = Some(self.snapshot());
+        }
+        self.view = View::Loading;
+    }
+
+    /// put a view back exactly as it was left
+    fn restore(&mut self, state: ViewState) {
+        self.view = state.view;
+        self.cached = true;
+        self.input = state.input;
+        self.rows_to_data_index = state.rows_to_data_index;
+        self.resp.episode_list = state.episode_list;
+        self.resp.franchise = state.franchise;
+        self.table_state = TableState::default()
+            .with_selected(state.selected)
+            .with_offset(state.offset);
+        self.selected_row = state.selected.unwrap_or(0);
+
+        // shows may have been followed or unfollowed from their episodes
+        if self.view == View::Watchlist
+            && self.rows_to_data_index.len() != self.watchlist.shows.len()
+        {
+            self.update_row_to_data_index();
+            self.move_selection_by(0);
+        }
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        // a line of the grid holds several episodes
+        if self.grid_columns > 0 {
+            let columns = self.grid_columns as isize;
+            return self.move_selection_by(if down { columns } else { -columns });
+        }
+        if down {
+            self.table_state.select_next();
+        } else {
+            self.table_state.select_previous();
+        }
+        if let Some(row) = self.table_state.selected() {
+            self.selected_row = row
+        }
+    }
+
+    /// move the selection by `delta` rows, stopping at the first and last row
+    fn move_selection_by(&mut self, delta: isize) {
+        let Some(last) = self.rows_to_data_index.len().checked_sub(1) else {
+            return;
+        };
+        let row = self
+            .table_state
+            .selected()
+            .unwrap_or(0)
+            .saturating_add_signed(delta)
+            .min(last);
+        self.table_state.select(Some(row));
+        self.selected_row = row;
+    }
+
+    /// number of rows that fit in the table
+    fn page_size(&self) -> usize {
+        if self.grid_columns > 0 {
+            let lines = self.table_area.height.saturating_sub(2).max(1) as usize;
+            return lines * self.grid_columns;
+        }
+        // border, header, its margin and the bottom border
+        (self.table_area.height.saturating_sub(4) / self.row_height()).max(1) as usize
+    }
+
+    /// select the episode typed in the go to prompt, matched exactly
+    fn jump_to_episode(&mut self) {
+        let Some((_, list)) = &self.resp.episode_list else {
+            return;
+        };
+        let index = find_episode(list, self.input.value().trim());
+        // stay in the prompt so a typo can be fixed
+        let Some(row) = index.and_then(|i| self.rows_to_data_index.iter().position(|r| *r == i))
+        else {
+            return;
+        };
+
+        self.goto_episode = false;
+        self.input.reset();
+        self.table_state.select(Some(row));
+        self.selected_row = row;
+    }
+
+    /// height of a row in the table of the current view
+    fn row_height(&self) -> u16 {
+        match self.view {
+            View::Search | View::Related => 3,
+            _ => 2,
+        }
+    }
+
+    /// row of the table, or cell of the episode grid, drawn at `position`
+    fn row_at(&self, position: Position) -> Option<usize> {
+        let Position { x, y } = position;
+        let inner = self.table_area.width.saturating_sub(2) as usize;
+        let row = if let Some(width) = inner.checked_div(self.grid_columns) {
+            let first = self.table_area.y + 1;
+            let left = self.table_area.x + 1;
+            if y < first || y + 1 >= self.table_area.bottom() || x < left {
+                return None;
+            }
+            let column = (x - left) as usize / width.max(1);
+            if column >= self.grid_columns {
+                return None;
+            }
+            (self.table_state.offset() + (y - first) as usize) * self.grid_columns + column
+        } else {
+            // border, header and its margin
+            let first = self.table_area.y + 2 + HEADER_MARGIN;
+            if y < first || y + 1 >= self.table_area.bottom() {
+                return None;
+            }
+            self.table_state.offset() + ((y - first) / self.row_height()) as usize
+        };
+        (row < self.rows_to_data_index.len()).then_some(row)
+    }
+
+    /// footer hint drawn at terminal column `x`
+    fn hint_at(&self, x: u16) -> Option<Hint> {
+        let hints = self.footer_hints();
+        let width: usize = hints.iter().map(|(span, _)| span.width()).sum();
+        let inner = self.footer_area.width.saturating_sub(2) as usize;
+        // cut off hints are not where they would be on one line
+        if width > inner {
+            return None;
+        }
+        // the footer is centered
+        let mut start = self.footer_area.x as usize + 1 + inner.saturating_sub(width) / 2;
+
+        for (span, hint) in hints {
+            let end = start + span.width();
+            if (start..end).contains(&(x as usize)) {
+                return hint;
+            }
+            start = end;
+        }
+        None
+    }
+
+    /// click selects a row, double click activates it, false when the app should quit
+    fn handle_mouse(&mut self, mouse: MouseEvent, tx: &mpsc::Sender<Option<Resp>>) -> bool {
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::ScrollDown => self.move_selection(true),
+            MouseEventKind::ScrollUp => self.move_selection(false),
+            MouseEventKind::Down(MouseButton::Left) if self.table_area.contains(position) => {
+                let Some(row) = self.row_at(position) else {
+                    return true;
+                };
+                let double = self.last_click.is_some_and(|(last, at)| {
+                    last == row && at.elapsed() < Duration::from_millis(400)
+                });
+                self.table_state.select(Some(row));
+                self.selected_row = row;
+
+                if double {
+                    self.last_click = None;
+                    self.activate(tx);
+                } else {
+                    self.last_click = Some((row, Instant::now()));
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) if self.footer_area.contains(position) => {
+                match self.hint_at(mouse.column) {
+                    Some(Hint::Up) => self.move_selection(false),
+                    Some(Hint::Down) => self.move_selection(true),
+                    Some(Hint::Select) => self.activate(tx),
+                    Some(Hint::Back) => return self.back(tx),
+                    Some(Hint::Tab) if matches!(self.view, View::Search) => {
+                        self.cycle_search_tab(tx, true)
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+        true
+    }
+
+    /// act on the selected row like pressing enter
+    fn activate(&mut self, tx: &mpsc::Sender<Option<Resp>>) {
+        match self.view {
+            View::Loading => (),
+            View::Search => {
+                if let Some(resp) = &self.resp.search {
+                    let Some(index) =
+                        selected_index(&self.rows_to_data_index, self.table_state.selected())
+                    else {
+                        return;
+                    };
+                    let id = resp[index].id.clone();
+                    self.request_episode_list(tx, id, self.mode);
+                }
+            }
+            View::Episode => self.request_episode_links(tx, false),
+            View::Related => {
+                if let Some(franchise) = &self.resp.franchise {
+                    let Some(index) =
+                        selected_index(&self.rows_to_data_index, self.table_state.selected())
+                    else {
+                        return;
+                    };
+                    let id = franchise[index].id.clone();
+                    self.request_episode_list(tx, id, self.mode);
+                }
+            }
+            View::Provider => {
+                if let Some((episode, links)) = &self.resp.episode_provider_list {
+                    let Some(index) =
+                        selected_index(&self.rows_to_data_index, self.table_state.selected())
+                    else {
+                        return;
+                    };
+                    let (provider, url) = links[index].clone();
+                    let episode = episode.clone();
+
+                    // stay to pick another provider when this one failed
+                    if self.play(&episode, &provider, &url) {
+                        self.back(tx);
+                    }
+                }
+            }
+            View::Library => {
+                let Some(row) = self.table_state.selected() else {
+                    return;
+                };
+                if let Some(index) = self.rows_to_data_index.get(row) {
+                    self.play_downloaded(*index);
+                }
+            }
+            View::Schedule => {
+                let Some(row) = self.table_state.selected() else {
+                    return;
+                };
+                let airing = self
+                    .rows_to_data_index
+                    .get(row)
+                    .and_then(|index| self.resp.schedule.as_ref().map(|s| s[*index].clone()));
+                if let Some(airing) = airing {
+                    self.request_episode_list(tx, airing.id, airing.mode);
+                }
+            }
+            View::Watchlist => {
+                let Some(row) = self.table_state.selected() else {
+                    return;
+                };
+                let followed = self
+                    .rows_to_data_index
+                    .get(row)
+                    .and_then(|index| self.watchlist.shows.iter().nth(*index));
+                if let Some((id, show)) = followed {
+                    let (id, mode) = (id.clone(), show.mode);
+                    self.request_episode_list(tx, id, mode);
+                }
+            }
+        }
+    }
+
+    fn main_loop(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        let (tx, rx) = mpsc::channel::<Option<Resp>>();
+
+        // has to be queried after the terminal entered the alternate screen
+        self.picker = Picker::from_query_stdio().unwrap_or_else(|_| Picker::halfblocks());
+
+        // the library is meant to work offline
+        if !matches!(self.view, View::Library) {
+            self.request_search(&tx, self.mode);
+        }
+
+        while !self.exit {
+            if let Ok(Some(resp)) = rx.try_recv() {
+                if let Some(page) = resp.info_page_failed {
+                    self.info_pages.remove(&page);
+                    self.info_page_failed = Some(page);
+                }
+                if let Some((error, unreachable)) = resp.error {
+                    self.last_error = Some(error);
+                    // a missing thumbnail says nothing about the view or the api
+                    if resp.background {
+                        continue;
+                    }
+                    // it would pick a row of some later response
+                    self.reselect = None;
+                    self.offline = unreachable;
+                    self.searching = false;
+                    if self.view == View::Loading
+                        && let Some(state) = self.before_loading.take()
+                    {
+                        self.restore(state);
+                    }
+                    continue;
+                }
+                if !resp.background {
+                    self.offline = false;
+                }
+
+                if let Some((id, infos)) = resp.episode_infos {
+                    if let Some((show, _)) = &mut self.resp.episode_list
+                        && show.id == id
+                    {
+                        show.episode_infos.extend(infos);
+                    }
+                    continue;
+                }
+
+                // thumbnails load in the background without touching the selection
+                if let Some((url, image)) = resp.thumbnail {
+                    if Some(&url) == self.thumbnail_url.as_ref() {
+                        self.thumbnail = Some(Thumbnail {
+                            url,
+                            protocol: self.picker.new_resize_protocol(image),
+                        });
+                    }
+                    continue;
+                }
+
+                // dead links sink to the bottom, the selected link stays selected
+                if let Some((url, probe)) = resp.probe {
+                    if let (View::Provider, Some((_, links))) =
+                        (&self.view, &self.resp.episode_provider_list)
+                        && links.iter().any(|(_, link)| *link == url)
+                    {
+                        let selected = self
+                            .table_state
+                            .selected()
+                            .and_then(|row| self.rows_to_data_index.get(row).copied());
+                        self.probes.insert(url, probe);
+                        self.update_row_to_data_index();
+                        if let Some(row) = selected.and_then(|index| {
+                            self.rows_to_data_index.iter().position(|i| *i == index)
+                        }) {
+                            self.table_state.select(Some(row));
+                            self.selected_row = row;
+                        }
+                    }
+                    continue;
+                }
+
+                if let Some(mode) = resp.mode {
+                    self.mode = mode;
+                }
+                if let Some(search_resp) = resp.search {
+                    self.searching = false;
+                    self.enter(View::Search);
+                    self.rows_to_data_index = (0..search_resp.len()).collect();
+                    self.resp.search = Some(search_resp);
+                }
+                if let Some(ep_list_resp) = resp.episode_list {
+                    // the same show re-fetched in another mode stays at the same level
+                    self.enter(View::Episode);
+                    self.rows_to_data_index = (0..ep_list_resp.1.len()).collect();
+                    self.saw_episodes(&ep_list_resp);
+                    // the first page of infos comes with the list, the others are gone
+                    let id = &ep_list_resp.0.id;
+                    self.info_pages.retain(|(show_id, _)| show_id != id);
+                    if let Some(first) = ep_list_resp.1.first() {
+                        self.info_pages
+                            .insert((ep_list_resp.0.id.clone(), first.clone()));
+                    }
+                    self.resp.episode_list = Some(ep_list_resp);
+                }
+                if let Some(franchise_resp) = resp.franchise {
+                    self.enter(View::Related);
+                    self.rows_to_data_index = (0..franchise_resp.len()).collect();
+                    self.resp.franchise = Some(franchise_resp);
+                }
+                if let Some(schedule_resp) = resp.schedule {
+                    // going back from the schedule leads to where it was opened
+                    if let Some(state) = self.before_loading.take() {
+                        self.nav_stack.push(state);
+                    }
+                    self.enter(View::Schedule);
+                    self.rows_to_data_index = (0..schedule_resp.len()).collect();
+                    self.resp.schedule = Some(schedule_resp);
+                }
+                if let Some(ep_provider_list_resp) = resp.episode_provider_list {
+                    if !self.pick_provider && self.autoplay(&ep_provider_list_resp) {
+                        self.resp.episode_provider_list = Some(ep_provider_list_resp);
+                        continue;
+                    }
+                    self.enter(View::Provider);
+                    self.rows_to_data_index = (0..ep_provider_list_resp.1.len()).collect();
+                    self.resp.episode_provider_list = Some(ep_provider_list_resp);
+                    self.request_probes(&tx);
+                }
+
+                let row = self.reselect_row().unwrap_or(0);
+                self.table_state.select(Some(row));
+                self.selected_row = row;
+                self.input.reset();
+                self.goto_episode = false;
+            }
+
+            terminal.draw(|frame| self.render(frame))?;
+
+            self.select_icon_animation();
+            self.request_thumbnail(&tx);
+            self.request_episode_infos(&tx);
+
+            if event::poll(Duration::from_millis(16))? {
+                let event = event::read()?;
+                if let Event::Mouse(mouse) = event
+                    && !self.handle_mouse(mouse, &tx)
+                {
+                    return Ok(());
+                }
+                if let Event::Key(key) = event {
+                    match key.code {
+                        event::KeyCode::Esc if self.goto_episode => {
+                            self.goto_episode = false;
+                            self.input.reset()
+                        }
+                        event::KeyCode::Esc => return Ok(()),
+                        event::KeyCode::Char('q')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
+                        {
+                            return Ok(());
+                        }
+                        event::KeyCode::Char('r')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Search) =>
+                        {
+                            self.remote_search(&tx)
+                        }
+                        event::KeyCode::Char('t')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Search | View::Episode) =>
+                        {
+                            self.cycle_mode(&tx)
+                        }
+                        event::KeyCode::Up
+                            if key.modifiers.contains(event::KeyModifiers::ALT)
+                                && matches!(self.view, View::Search) =>
+                        {
+                            self.recall_query(true)
+                        }
+                        event::KeyCode::Down
+                            if key.modifiers.contains(event::KeyModifiers::ALT)
+                                && matches!(self.view, View::Search) =>
+                        {
+                            self.recall_query(false)
+                        }
+                        event::KeyCode::Down => self.move_selection(true),
+                        event::KeyCode::Char('j') | event::KeyCode::Char('n')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
+                        {
+                            self.move_selection(true)
+                        }
+                        event::KeyCode::Up => self.move_selection(false),
+                        event::KeyCode::Char('k') | event::KeyCode::Char('p')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
+                        {
+                            self.move_selection(false)
+                        }
+                        event::KeyCode::Right if self.grid_columns > 0 => self.move_selection_by(1),
+                        event::KeyCode::Left if self.grid_columns > 0 => self.move_selection_by(-1),
+                        event::KeyCode::Char('f')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Episode) =>
+                        {
+                            if let Some((show, _)) = &self.resp.episode_list {
+                                let show = show.clone();
+                                let api_clone = self.api.clone();
+                                spawn_request(
+                                    &tx,
+                                    "Error getting related shows",
+                                    false,
+                                    move || {
+                                        Ok(Resp {
+                                            franchise: Some(api_clone.get_franchise(&show)?),
+                                            ..Default::default()
+                                        })
+                                    },
+                                );
+                            }
+                        }
+                        event::KeyCode::Char('o')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Episode) =>
+                        {
+                            self.request_episode_links(&tx, true)
+                        }
+                        event::KeyCode::Char('l')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Search) =>
+                        {
+                            self.open_library()
+                        }
+                        event::KeyCode::Char('w')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Library) =>
+                        {
+                            self.toggle_watched()
+                        }
+                        event::KeyCode::Char('w')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Search) =>
+                        {
+                            self.enter(View::Watchlist);
+                            self.input.reset();
+                            self.rows_to_data_index = (0..self.watchlist.shows.len()).collect();
+                            self.table_state.select(Some(0));
+                            self.selected_row = 0;
+                        }
+                        event::KeyCode::Char('s')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Search | View::Watchlist) =>
+                        {
+                            self.request_schedule(&tx)
+                        }
+                        event::KeyCode::Char('d')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Search) =>
+                        {
+                            self.search_descriptions = !self.search_descriptions;
+                            self.update_row_to_data_index();
+                            self.select_first();
+                        }
+                        event::KeyCode::Char('a')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Episode) =>
+                        {
+                            self.toggle_follow()
+                        }
+                        event::KeyCode::Tab if matches!(self.view, View::Search) => {
+                            self.cycle_search_tab(&tx, true)
+                        }
+                        event::KeyCode::BackTab if matches!(self.view, View::Search) => {
+                            self.cycle_search_tab(&tx, false)
+                        }
+                        event::KeyCode::Backspace | event::KeyCode::Char('h')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL) =>
+                        {
+                            if !self.back(&tx) {
+                                return Ok(());
+                            }
+                        }
+                        event::KeyCode::Enter if self.goto_episode => self.jump_to_episode(),
+                        event::KeyCode::Enter => self.activate(&tx),
+                        event::KeyCode::Char('g')
+                            if key.modifiers.contains(event::KeyModifiers::CONTROL)
+                                && matches!(self.view, View::Episode) =>
+                        {
+                            // the whole list, so the rows line up with the episodes again
+                            self.input.reset();
+                            self.update_row_to_data_index();
+                            self.goto_episode = true
+                        }
+                        event::KeyCode::PageDown => {
+                            self.move_selection_by(self.page_size() as isize)
+                        }
+                        event::KeyCode::PageUp => {
+                            self.move_selection_by(-(self.page_size() as isize))
+                        }
+                        // they move the cursor once there is something typed
+                        event::KeyCode::Home if self.input.value().is_empty() => {
+                            self.move_selection_by(isize::MIN)
+                        }
+                        event::KeyCode::End if self.input.value().is_empty() => {
+                            self.move_selection_by(isize::MAX)
+                        }
+                        _ if self.goto_episode => {
+                            self.input.handle_event(&event);
+                        }
+                        _ => {
+                            self.input.handle_event(&event);
+                            self.update_row_to_data_index();
+                            self.select_first();
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// pattern of the fuzzy filter, empty while the input holds an episode to go to
+    fn filter_pattern(&self) -> Pattern {
+        let filter = if self.goto_episode {
+            ""
+        } else {
+            self.input.value()
+        };
+        Pattern::new(
+            filter,
+            CaseMatching::Smart,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+        )
+    }
+
+    fn fuzzy_reorder(&mut self, str_vec: Vec<String>, buff: &mut Vec<char>) {
+        let pattern = self.filter_pattern();
+
+        let mut vec: Vec<(usize, u32)> = Vec::new();
+        for (i, e_str) in str_vec.iter().enumerate() {
+            if let Some(score) = pattern.score(Utf32Str::new(e_str, buff), &mut self.matcher) {
+                vec.push((i, score));
+            };
+        }
+
+        vec.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        self.rows_to_data_index = vec.into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// select the first row, or nothing when the filter left no rows
+    fn select_first(&mut self) {
+        let first = (!self.rows_to_data_index.is_empty()).then_some(0);
+        self.table_state.select(first);
+    }
+
+    /// update the index of rows to data pointer vec
+    fn update_row_to_data_index(&mut self) {
+        let mut buf = Vec::new();
+
+        match self.view {
+            View::Loading => (),
+            View::Search => {
+                if let Some(resp) = &self.resp.search {
+                    let query = self.input.value();
+                    let described: Vec<usize> = resp
+                        .iter()
+                        .map(|item| {
+                            if self.search_descriptions {
+                                count_matches(&item.description, query)
+                            } else {
+                                0
+                            }
+                        })
+                        .collect();
+                    self.fuzzy_reorder(
+                        resp.iter()
+                            .map(|item| {
+                                if let Some(english_name) = &item.english_name {
+                                    format!("{} {}", item.name, english_name)
+                                } else {
+                                    item.name.to_string()
+                                }
+                            })
+                            .collect(),
+                        &mut buf,
+                    );
+                    // shows matched only by their description come after the names
+                    let mut extra: Vec<usize> = (0..described.len())
+                        .filter(|i| described[*i] > 0 && !self.rows_to_data_index.contains(i))
+                        .collect();
+                    extra.sort_by_key(|i| std::cmp::Reverse(described[*i]));
+                    self.rows_to_data_index.extend(extra);
+                }
+            }
+
+            View::Episode => {
+                if let Some((_, resp)) = &self.resp.episode_list {
+                    self.fuzzy_reorder(resp.iter().map(|item| item.to_string()).collect(), &mut buf)
+                }
+            }
+
+            View::Provider => {
+                if let Some((_, resp)) = &self.resp.episode_provider_list {
+                    let dead: Vec<bool> = resp
+                        .iter()
+                        .map(|(_, url)| self.probes.get(url).is_some_and(|p| !p.is_alive()))
+                        .collect();
+                    self.fuzzy_reorder(
+                        resp.iter().map(|item| item.0.to_string()).collect(),
+                        &mut buf,
+                    );
+                    // stable, so the fuzzy order is kept within alive and dead links
+                    self.rows_to_data_index.sort_by_key(|index| dead[*index]);
+                }
+            }
+
+            View::Related => {
+                if let Some(resp) = &self.resp.franchise {
+                    self.fuzzy_reorder(
+                        resp.iter()
+                            .map(|item| format!("{} {}", item.name, item.relation))
+                            .collect(),
+                        &mut buf,
+                    )
+                }
+            }
+
+            View::Schedule => {
+                if let Some(resp) = &self.resp.schedule {
+                    self.fuzzy_reorder(
+                        resp.iter()
+                            .map(|airing| format!("{} {}", airing.name, airing.weekday))
+                            .collect(),
+                        &mut buf,
+                    )
+                }
+            }
+
+            View::Watchlist => self.fuzzy_reorder(
+                self.watchlist
+                    .shows
+                    .values()
+                    .map(|show| show.name.clone())
+                    .collect(),
+                &mut buf,
+            ),
+
+            View::Library => self.fuzzy_reorder(
+                self.library
+                    .entries
+                    .iter()
+                    .map(|entry| format!("{} {} {}", entry.show_name, entry.episode, entry.mode))
+                    .collect(),
+                &mut buf,
+            ),
+        }
+    }
+
+    fn render_search_input(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(
+            Paragraph::new(self.input.value())
+                .alignment(HorizontalAlignment::Center)
+                .block(
+                    Block::bordered()
+                        .title_bottom(self.breadcrumb())
+                        .title(if self.goto_episode {
+                            "Go to episode"
+                        } else {
+                            "Fuzzy Search"
+                        })
+                        .title(match self.view {
+                            View::Search => line![
+                                "ctrl+r ",
+                                "Remote Search ",
+                                "ctrl+l ",
+                                "Library ",
+                                "ctrl+w ",
+                                "Watchlist ",
+                                "ctrl+s ",
+                                "Schedule ",
+                                "alt+↑/↓ ",
+                                "History ",
+                                "ctrl+d ",
+                                if self.search_descriptions {
+                                    span!(Style::new().green(); "Descriptions ")
+                                } else {
+                                    span!("Descriptions ")
+                                },
+                                "ctrl+t ",
+                                span!(Style::new().magenta().bold(); self.mode.as_str())
+                            ]
+                            .right_aligned(),
+                            View::Episode => line![
+                                "ctrl+g ",
+                                "Go to ",
+                                "ctrl+a ",
+                                if self.resp.episode_list.as_ref().is_some_and(|(show, _)| self
+                                    .watchlist
+                                    .shows
+                                    .contains_key(&show.id))
+                                {
+                                    "Unfollow "
+                                } else {
+                                    "Follow "
+                                },
+                                "ctrl+o ",
+                                "Providers ",
+                                "ctrl+f ",
+                                "Franchise ",
+                                "ctrl+t ",
+                                span!(Style::new().magenta().bold(); self.mode.as_str())
+                            ]
+                            .right_aligned(),
+                            View::Library => line!["ctrl+w ", "Watched"].right_aligned(),
+                            _ => Line::default(),
+                        })
+                        .title_style(Style::new().green().bold())
+                        .style(Style::new().red())
+                        .border_type(BorderType::Rounded),
+                ),
+            area,
+        );
+    }
+
+    /// render the catalogue tabs above the search results
+    fn render_search_tabs(&self, frame: &mut Frame, area: Rect) {
+        let search_title = if self.query.is_empty() {
+            "Search".to_string()
+        } else {
+            format!("Search: {}", self.query)
+        };
+        let titles =
+            std::iter::once(search_title).chain(Browse::ALL.iter().map(|b| b.title().to_string()));
+        let selected = self
+            .browse
+            .and_then(|browse| Browse::ALL.iter().position(|b| *b == browse))
+            .map_or(0, |i| i + 1);
+
+        frame.render_widget(
+            Tabs::new(titles)
+                .select(selected)
+                .style(Style::new().cyan())
+                .highlight_style(Style::new().bold().black().on_cyan())
+                .block(
+                    Block::bordered()
+                        .border_type(BorderType::Rounded)
+                        .style(Style::new().red()),
+                ),
+            area,
+        );
+    }
+
+    /// render the skeleton before data is there
+    fn render_skeleton(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(
+            Paragraph::new(include_str!("../assets/art.txt"))
+                .style(Style::default().bold().cyan())
+                .centered()
+                .block(
+                    Block::bordered()
+                        .style(Style::new().red())
+                        .border_type(BorderType::Rounded),
+                ),
+            area,
+        );
+    }
+
+    fn render_search_result(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(data) = &self.resp.search else {
+            return;
+        };
+        let pattern = self.filter_pattern();
+
+        let mut rows = vec![];
+        for index in &self.rows_to_data_index {
+            let item = &data[*index];
+            let ep_counts = Mode::ALL.map(|mode| {
+                let count = item
+                    .available_episodes
+                    .as_ref()
+                    .and_then(|map| map.get(mode.as_str()))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0);
+                let style = if mode == self.mode {
+                    Style::new().bold().green()
+                } else {
+                    Style::new().bold()
+                };
+                Cell::from(
+                    Line::styled(count.to_string(), style).alignment(HorizontalAlignment::Center),
+                )
+            });
+
+            let english_name = item.english_name.as_deref().unwrap_or(&item.name);
+            // matched like the filter does, against both names at once
+            let name_len = item.name.chars().count() as u32;
+            let indices = match &item.english_name {
+                Some(english_name) => match_indices(
+                    &pattern,
+                    &mut self.matcher,
+                    &format!("{} {}", item.name, english_name),
+                ),
+                None => match_indices(&pattern, &mut self.matcher, &item.name),
+            };
+            let (name_indices, english_indices) = split_indices(&indices, name_len);
+            let mut name = highlight(&item.name, &name_indices, Style::new().magenta().bold());
+            name.push(self.new_badge(&item.id));
+
+            let mut cells = vec![
+                Cell::from(
+                    Line::styled((index + 1).to_string(), Style::default().yellow().bold())
+                        .alignment(HorizontalAlignment::Center),
+                ),
+                Cell::from(vec![
+                    Line::from(name),
+                    Line::from(if english_name != item.name {
+                        highlight(english_name, &english_indices, Style::new().red().bold())
+                    } else {
+                        Vec::new()
+                    }),
+                ]),
+            ];
+            cells.extend(ep_counts);
+
+            rows.push(Row::new(cells).height(3))
+        }
+
+        let header = Row::new(vec![
+            Line::from("#").alignment(HorizontalAlignment::Center),
+            Line::from("Name").alignment(HorizontalAlignment::Center),
+            Line::from("Sub").alignment(HorizontalAlignment::Center),
+            Line::from("Dub").alignment(HorizontalAlignment::Center),
+            Line::from("Raw").alignment(HorizontalAlignment::Center),
+        ])
+        .style(Style::default().bold().yellow())
+        .bottom_margin(HEADER_MARGIN);
+
+        frame.render_stateful_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Percentage(5),
+                    Constraint::Fill(1),
+                    Constraint::Length(5),
+                    Constraint::Length(5),
+                    Constraint::Length(5),
+                ],
+            )
+            .header(header)
+            .style(Style::new().fg(Color::Cyan))
+            .highlight_symbol(self.select_icon.to_string())
+            .row_highlight_style(Style::new().bg(Color::Cyan).fg(Color::Black))
+            .block(Block::bordered().border_type(BorderType::Rounded)),
+            area,
+            &mut self.table_state,
+        );
+    }
+
+    fn render_episode_list(&mut self, frame: &mut Frame, area: Rect) {
+        let Some((show, ep_list)) = &self.resp.episode_list else {
+            return;
+        };
+        if ep_list.len() > GRID_MIN_EPISODES {
+            return self.render_episode_grid(frame, area);
+        }
+
+        let pattern = self.filter_pattern();
+        let mut rows = Vec::new();
+        for index in &self.rows_to_data_index {
+            let item = ep_list[*index].as_str();
+            let info = show.episode_infos.get(item);
+            let title = info.and_then(|i| i.notes.as_deref()).unwrap_or_default();
+            let aired = info
+                .and_then(|i| i.upload_date(self.mode.as_str()))
+                .unwrap_or_default();
+            let duration = info
+                .and_then(|i| i.duration_minutes())
+                .map(|m| format!("{} min", m))
+                .unwrap_or_default();
+            let modes = show.episode_modes.get(item);
+            let availability = Line::from(
+                Mode::ALL
+                    .iter()
+                    .map(|mode| {
+                        let label = format!("{} ", mode.as_str());
+                        if modes.is_some_and(|m| m.contains(mode)) {
+                            Span::styled(label, Style::new().green().bold())
+                        } else {
+                            Span::styled(label, Style::new().dark_gray())
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .alignment(HorizontalAlignment::Center);
+
+            rows.push(
+                Row::new(vec![
+                    Line::from(highlight(
+                        item,
+                        &match_indices(&pattern, &mut self.matcher, item),
+                        self.episode_style(&show.id, item),
+                    ))
+                    .alignment(HorizontalAlignment::Center),
+                    Line::styled(title, Style::new().magenta().bold()),
+                    Line::styled(aired, Style::new().yellow())
+                        .alignment(HorizontalAlignment::Center),
+                    Line::from(duration).alignment(HorizontalAlignment::Center),
+                    availability,
+                ])
+                .height(2),
+            )
+        }
+
+        let header = Row::new(vec![
+            Line::from("Episodes").alignment(HorizontalAlignment::Center),
+            Line::from("Title").alignment(HorizontalAlignment::Center),
+            Line::from("Aired").alignment(HorizontalAlignment::Center),
+            Line::from("Duration").alignment(HorizontalAlignment::Center),
+            Line::from("Available").alignment(HorizontalAlignment::Center),
+        ])
+        .style(Style::default().bold().yellow())
+        .bottom_margin(HEADER_MARGIN);
+
+        frame.render_stateful_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(10),
+                    Constraint::Fill(1),
+                    Constraint::Length(12),
+                    Constraint::Length(10),
+                    Constraint::Length(13),
+                ],
+            )
+            .header(header)
+            .style(Style::new().fg(Color::Cyan))
+            .highlight_symbol(self.select_icon.to_string())
+            .row_highlight_style(Style::new().bg(Color::LightCyan).fg(Color::Black))
+            .block(Block::bordered().border_type(BorderType::Rounded)),
+            area,
+            &mut self.table_state,
+        );
+    }
+
+    /// episodes of a long show as a grid of their numbers
+    fn render_episode_grid(&mut self, frame: &mut Frame, area: Rect) {
+        let Some((show, ep_list)) = &self.resp.episode_list else {
+            return;
+        };
+
+        let inner = Block::bordered().inner(area);
+        let label = ep_list.iter().map(|ep| ep.len()).max().unwrap_or(0) + 2;
+        let columns = (inner.width as usize / label.max(5)).max(1);
+        let width = (inner.width as usize / columns).max(1);
+        let lines = (inner.height as usize).max(1);
+        self.grid_columns = columns;
+        // a table drops the selection by itself when there are no rows
+        if self.rows_to_data_index.is_empty() {
+            self.table_state.select(None);
+        }
+
+        // scroll by whole lines of the grid to keep the selection in view
+        let selected = self.table_state.selected().unwrap_or(0);
+        let line = selected / columns;
+        let mut offset = self.table_state.offset();
+        if line < offset {
+            offset = line;
+        } else if line >= offset + lines {
+            offset = line + 1 - lines;
+        }
+        *self.table_state.offset_mut() = offset;
+
+        let visible: Vec<_> = self
+            .rows_to_data_index
+            .chunks(columns)
+            .skip(offset)
+            .take(lines)
+            .collect();
+        let pattern = self.filter_pattern();
+        let mut grid = Vec::new();
+        for (i, chunk) in visible.iter().enumerate() {
+            let mut spans = Vec::new();
+            for (j, index) in chunk.iter().enumerate() {
+                let episode = ep_list[*index].as_str();
+                let style = if (offset + i) * columns + j == selected {
+                    Style::new().bg(Color::LightCyan).fg(Color::Black)
+                } else {
+                    self.episode_style(&show.id, episode)
+                };
+                // centered by hand, the matched chars get their own spans
+                let padding = width.saturating_sub(episode.chars().count());
+                let indices = match_indices(&pattern, &mut self.matcher, episode);
+                spans.push(Span::styled(" ".repeat(padding / 2), style));
+                spans.extend(highlight(episode, &indices, style));
+                spans.push(Span::styled(" ".repeat(padding - padding / 2), style));
+            }
+            grid.push(Line::from(spans));
+        }
+
+        let first = visible.first().and_then(|chunk| chunk.first());
+        let last = visible.last().and_then(|chunk| chunk.last());
+        let title = match first.zip(last) {
+            Some((first, last)) => format!(
+                "Episodes {}-{} of {}",
+                ep_list[*first],
+                ep_list[*last],
+                ep_list.len()
+            ),
+            None => format!("Episodes of {}", ep_list.len()),
+        };
+
+        frame.render_widget(
+            Paragraph::new(grid).block(
+                Block::bordered()
+                    .title(title)
+                    .border_type(BorderType::Rounded),
+            ),
+            area,
+        );
+    }
+
+    /// green once finished, yellow when there is a position to resume from
+    fn episode_style(&self, show_id: &str, episode: &str) -> Style {
+        let finished =
+            self.history
+                .shows
+                .get(show_id)
+                .is_some_and(|show| show.finished.iter().any(|ep| ep == episode))
+                || self.library.entries.iter().any(|entry| {
+                    entry.watched && entry.show_id == show_id && entry.episode == episode
+                });
+
+        if finished {
+            Style::new().green().bold()
+        } else if self
+            .resume
+            .contains_key(&(show_id.to_string(), episode.to_string()))
+        {
+            Style::new().yellow().bold()
+        } else {
+            Style::new().red().bold()
+        }
+    }
+
+    fn render_episode_providers(&mut self, frame: &mut Frame, area: Rect) {
+        let Some((_, links_list)) = &self.resp.episode_provider_list else {
+            return;
+        };
+        let pattern = self.filter_pattern();
+
+        let mut rows = Vec::new();
+        for index in &self.rows_to_data_index {
+            let (provider_name, link) = &links_list[*index];
+
+            let (status, latency, content_type, size) = match self.probes.get(link) {
+                None => (
+                    span!(Color::DarkGray; "…"),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                ),
+                Some(probe) => {
+                    let status = match probe.status {
+                        Some(code) if probe.is_alive() => span!(Color::Green; "{}", code),
+                        Some(code) => span!(Color::Red; "{}", code),
+                        None => span!(Color::Red; "dead"),
+                    };
+                    (
+                        status,
+                        format!("{} ms", probe.latency.as_millis()),
+                        probe.content_type.clone().unwrap_or_default(),
+                        probe.size.map(format_size).unwrap_or_default(),
+                    )
+                }
+            };
+
+            rows.push(
+                Row::new(vec![
+                    Line::from(highlight(
+                        provider_name,
+                        &match_indices(&pattern, &mut self.matcher, provider_name),
+                        Style::new().red().bold(),
+                    ))
+                    .alignment(HorizontalAlignment::Center),
+                    Line::from(status).alignment(HorizontalAlignment::Center),
+                    Line::from(latency).alignment(HorizontalAlignment::Center),
+                    Line::from(content_type).alignment(HorizontalAlignment::Center),
+                    Line::from(size).alignment(HorizontalAlignment::Center),
+                ])
+                .height(2),
+            );
+        }
+
+        let header = Row::new(vec![
+            Line::from("Provider").alignment(HorizontalAlignment::Center),
+            Line::from("Status").alignment(HorizontalAlignment::Center),
+            Line::from("Latency").alignment(HorizontalAlignment::Center),
+            Line::from("Type").alignment(HorizontalAlignment::Center),
+            Line::from("Size").alignment(HorizontalAlignment::Center),
+        ])
+        .style(Style::default().bold().yellow())
+        .bottom_margin(HEADER_MARGIN);
+
+        frame.render_stateful_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Fill(2),
+                    Constraint::Length(8),
+                    Constraint::Length(10),
+                    Constraint::Fill(2),
+                    Constraint::Length(10),
+                ],
+            )
+            .header(header)
+            .style(Style::new().fg(Color::Cyan))
+            .highlight_symbol(self.select_icon.to_string())
+            .row_highlight_style(Style::new().bg(Color::LightCyan).fg(Color::Black))
+            .block(Block::bordered().border_type(BorderType::Rounded)),
+            area,
+            &mut self.table_state,
+        );
+    }
+
+    /// count of new episodes of a followed show, empty when there are none
+    fn new_badge(&self, id: &str) -> Span<'static> {
+        match self.watchlist.unseen(id) {
+            0 => Span::default(),
+            count => span!(Style::new().black().on_yellow().bold(); " +{} new ", count),
+        }
+    }
+
+    /// render the followed shows with their new episodes
+    fn render_watchlist(&mut self, frame: &mut Frame, area: Rect) {
+        let shows: Vec<_> = self.watchlist.shows.iter().collect();
+
+        let mut rows = Vec::new();
+        for index in &self.rows_to_data_index {
+            let (id, show) = shows[*index];
+            rows.push(
+                Row::new(vec![
+                    Line::from(vec![
+                        Span::styled(show.name.as_str(), Style::new().magenta().bold()),
+                        Span::raw(" "),
+                        self.new_badge(id),
+                    ]),
+                    Line::from(show.mode.as_str()).alignment(HorizontalAlignment::Center),
+                    Line::from(show.episodes.len().to_string())
+                        .alignment(HorizontalAlignment::Center),
+                ])
+                .height(2),
+            );
+        }
+
+        let header = Row::new(vec![
+            Line::from("Show"),
+            Line::from("Mode").alignment(HorizontalAlignment::Center),
+            Line::from("Episodes").alignment(HorizontalAlignment::Center),
+        ])
+        .style(Style::default().bold().yellow())
+        .bottom_margin(HEADER_MARGIN);
+
+        frame.render_stateful_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Fill(1),
+                    Constraint::Length(6),
+                    Constraint::Length(10),
+                ],
+            )
+            .header(header)
+            .style(Style::new().fg(Color::Cyan))
+            .highlight_symbol(self.select_icon.to_string())
+            .row_highlight_style(Style::new().bg(Color::LightCyan).fg(Color::Black))
+            .block(
+                Block::bordered()
+                    .title("Watchlist")
+                    .border_type(BorderType::Rounded),
+            ),
+            area,
+            &mut self.table_state,
+        );
+    }
+
+    /// render the airing calendar grouped by weekday
+    fn render_schedule(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(schedule) = &self.resp.schedule else {
+            return;
+        };
+
+        let mut rows = Vec::new();
+        let mut previous_day = None;
+        for index in &self.rows_to_data_index {
+            let airing = &schedule[*index];
+            // the weekday is only written above its first show
+            let day = if previous_day == Some(airing.weekday) {
+                Line::default()
+            } else {
+                Line::styled(airing.weekday, Style::new().red().bold())
+            };
+            previous_day = Some(airing.weekday);
+
+            let time = if airing.due {
+                span!(Color::Yellow; "due")
+            } else {
+                span!(Color::Green; "{}", &format_unix(airing.at)[11..])
+            };
+
+            rows.push(
+                Row::new(vec![
+                    day,
+                    Line::from(time).alignment(HorizontalAlignment::Center),
+                    Line::from(vec![
+                        Span::styled(airing.name.as_str(), Style::new().magenta().bold()),
+                        Span::raw(format!(" ({})", airing.mode.as_str())),
+                    ]),
+                    Line::from(airing.episode.clone().unwrap_or_default())
+                        .alignment(HorizontalAlignment::Center),
+                ])
+                .height(2),
+            );
+        }
+
+        let header = Row::new(vec![
+            Line::from("Day"),
+            Line::from("UTC").alignment(HorizontalAlignment::Center),
+            Line::from("Show"),
+            Line::from("Episode").alignment(HorizontalAlignment::Center),
+        ])
+        .style(Style::default().bold().yellow())
+        .bottom_margin(HEADER_MARGIN);
+
+        frame.render_stateful_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(10),
+                    Constraint::Length(6),
+                    Constraint::Fill(1),
+                    Constraint::Length(8),
+                ],
+            )
+            .header(header)
+            .style(Style::new().fg(Color::Cyan))
+            .highlight_symbol(self.select_icon.to_string())
+            .row_highlight_style(Style::new().bg(Color::LightCyan).fg(Color::Black))
+            .block(
+                Block::bordered()
+                    .title("Schedule")
+                    .border_type(BorderType::Rounded),
+            ),
+            area,
+            &mut self.table_state,
+        );
+    }
+
+    /// render the new episodes of the selected followed show
+    fn render_followed(&self, frame: &mut Frame, area: Rect) {
+        let Some((_, show)) = self
+            .rows_to_data_index
+            .get(self.selected_row)
+            .and_then(|index| self.watchlist.shows.iter().nth(*index))
+        else {
+            return;
+        };
+
+        let new = if show.unseen.is_empty() {
+            "none".to_string()
+        } else {
+            show.unseen.join(", ")
+        };
+        let lines = vec![
+            line![span!(Style::new().bold().red(); "{}", show.name)],
+            line![span!(Color::Yellow; "New episodes "), new],
+            line![
+                span!(Color::Yellow; "Latest "),
+                show.episodes.last().cloned().unwrap_or_default()
+            ],
+        ];
+
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .style(Style::new().cyan()),
+            ),
+            area,
+        );
+    }
+
+    /// render the downloaded episodes grouped by show
+    fn render_library(&mut self, frame: &mut Frame, area: Rect) {
+        let mut rows = Vec::new();
+        let mut previous_show = None;
+        for index in &self.rows_to_data_index {
+            let entry = &self.library.entries[*index];
+            // the show name is only written above its first episode
+            let show = if previous_show == Some(&entry.show_id) {
+                Line::default()
+            } else {
+                Line::styled(&entry.show_name, Style::new().red().bold())
+            };
+            previous_show = Some(&entry.show_id);
+
+            let watched = if entry.watched {
+                span!(Color::Green; "watched")
+            } else {
+                span!(Color::Yellow; "new")
+            };
+
+            rows.push(
+                Row::new(vec![
+                    show,
+                    Line::from(entry.episode.as_str()).alignment(HorizontalAlignment::Center),
+                    Line::from(entry.mode.as_str()).alignment(HorizontalAlignment::Center),
+                    Line::from(watched).alignment(HorizontalAlignment::Center),
+                ])
+                .height(2),
+            );
+        }
+
+        let header = Row::new(vec![
+            Line::from("Show"),
+            Line::from("Episode").alignment(HorizontalAlignment::Center),
+            Line::from("Mode").alignment(HorizontalAlignment::Center),
+            Line::from("Watched").alignment(HorizontalAlignment::Center),
+        ])
+        .style(Style::default().bold().yellow())
+        .bottom_margin(HEADER_MARGIN);
+
+        frame.render_stateful_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Fill(1),
+                    Constraint::Length(9),
+                    Constraint::Length(6),
+                    Constraint::Length(9),
+                ],
+            )
+            .header(header)
+            .style(Style::new().fg(Color::Cyan))
+            .highlight_symbol(self.select_icon.to_string())
+            .row_highlight_style(Style::new().bg(Color::LightCyan).fg(Color::Black))
+            .block(
+                Block::bordered()
+                    .title(format!("Library: {}", self.library.dir.display()))
+                    .border_type(BorderType::Rounded),
+            ),
             area,
+            &mut self.table_state,
         );
     }
 
-    /// render the skeleton before data is there
-    fn render_skeleton(&self, frame: &mut Frame, area: Rect) {
+    /// render where the selected download is stored
+    fn render_library_entry(&self, frame: &mut Frame, area: Rect) {
+        let Some(entry) = self
+            .rows_to_data_index
+            .get(self.selected_row)
+            .map(|index| &self.library.entries[*index])
+        else {
+            return;
+        };
+
+        let mut lines = vec![
+            line![span!(Style::new().bold().red(); "{}", entry.show_name)],
+            line![
+                span!(Color::Yellow; "Episode "),
+                format!("{} ({})", entry.episode, entry.mode)
+            ],
+            line![
+                span!(Color::Yellow; "File "),
+                self.library.path(entry).display().to_string()
+            ],
+        ];
+        if let Some(minutes) = entry.duration_minutes {
+            lines.push(line![
+                span!(Color::Yellow; "Duration "),
+                format!("{} min", minutes)
+            ]);
+        }
+        for subtitle in &entry.subtitles {
+            lines.push(line![span!(Color::Yellow; "Subtitle "), subtitle.as_str()]);
+        }
+
         frame.render_widget(
-            Paragraph::new(include_str!("../assets/art.txt"))
-                .style(Style::default().bold().cyan())
-                .centered()
-                .block(
-                    Block::bordered()
-                        .style(Style::new().red())
-                        .border_type(BorderType::Rounded),
-                ),
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .style(Style::new().cyan()),
+            ),
             area,
         );
     }
 
-    fn render_search_result(&mut self, frame: &mut Frame, area: Rect) {
-        let Some(data) = &self.resp.search else {
+    /// render the franchise as a timeline in airing order
+    fn render_franchise(&mut self, frame: &mut Frame, area: Rect) {
+        let Some(franchise) = &self.resp.franchise else {
             return;
         };
+        let pattern = self.filter_pattern();
 
-        let mut rows = vec![];
+        let mut rows = Vec::new();
         for index in &self.rows_to_data_index {
-            let item = &data[*index];
-            let ep_count = item
-                .available_episodes
-                .as_ref()
-                .and_then(|map| map.get("sub"))
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0)
-                .to_string();
-
+            let item = &franchise[*index];
+            let aired = item
+                .aired_start
+                .and_then(|d| Some(format!("{}-{:02}", d.year?, d.month.unwrap_or(1))))
+                .unwrap_or_else(|| "TBA".to_string());
             let english_name = item.english_name.as_deref().unwrap_or(&item.name);
+            // the filter also matches the relation that follows the name
+            let name_len = item.name.chars().count() as u32;
+            let name_indices: Vec<u32> = match_indices(
+                &pattern,
+                &mut self.matcher,
+                &format!("{} {}", item.name, item.relation),
+            )
+            .into_iter()
+            .filter(|i| *i < name_len)
+            .collect();
+            let relation_style = if item.relation == "this" {
+                Style::new().green().bold()
+            } else {
+                Style::new().red().bold()
+            };
 
             rows.push(
                 Row::new(vec![
                     Cell::from(
-                        Line::styled((index + 1).to_string(), Style::default().yellow().bold())
+                        Line::styled(aired, Style::default().yellow().bold())
                             .alignment(HorizontalAlignment::Center),
                     ),
                     Cell::from(vec![
-                        Line::from(Span::styled(
-                            item.name.as_str(),
+                        Line::from(highlight(
+                            &item.name,
+                            &name_indices,
                             Style::new().magenta().bold(),
                         )),
                         Line::from(Span::styled(
@@ -465,29 +2737,35 @@ impl App {
                         )),
                     ]),
                     Cell::from(
-                        Line::styled(ep_count, Style::new().bold())
+                        Line::styled(item.kind.as_deref().unwrap_or("?"), Style::new().bold())
+                            .alignment(HorizontalAlignment::Center),
+                    ),
+                    Cell::from(
+                        Line::styled(item.relation.as_str(), relation_style)
                             .alignment(HorizontalAlignment::Center),
                     ),
                 ])
                 .height(3),
-            )
+            );
         }
 
         let header = Row::new(vec![
-            Line::from("#").alignment(HorizontalAlignment::Center),
+            Line::from("Aired").alignment(HorizontalAlignment::Center),
             Line::from("Name").alignment(HorizontalAlignment::Center),
-            Line::from("Episodes").alignment(HorizontalAlignment::Center),
+            Line::from("Type").alignment(HorizontalAlignment::Center),
+            Line::from("Relation").alignment(HorizontalAlignment::Center),
         ])
         .style(Style::default().bold().yellow())
-        .bottom_margin(1);
+        .bottom_margin(HEADER_MARGIN);
 
         frame.render_stateful_widget(
             Table::new(
                 rows,
                 [
-                    Constraint::Percentage(5),
-                    Constraint::Percentage(85),
+                    Constraint::Length(9),
                     Constraint::Fill(1),
+                    Constraint::Length(8),
+                    Constraint::Length(12),
                 ],
             )
             .header(header)
@@ -500,78 +2778,143 @@ impl App {
         );
     }
 
-    fn render_episode_list(&mut self, frame: &mut Frame, area: Rect) {
-        let Some((_, ep_list, _)) = &self.resp.episode_list else {
-            return;
+    /// render metadata of the show whose episodes are listed
+    fn render_show_detail(&self, frame: &mut Frame, area: Rect, show: &ShowDetail) {
+        let field = |label: &'static str, value: String| {
+            line![span!(Style::new().yellow().bold(); label), value]
         };
 
-        let mut rows = Vec::new();
-        for index in &self.rows_to_data_index {
-            let item = ep_list[*index].as_str();
-            rows.push(
-                Row::new(vec![
-                    Line::styled(item, Style::new().red().bold())
-                        .alignment(HorizontalAlignment::Center),
-                ])
-                .height(2),
-            )
+        let mut lines = Vec::new();
+        // the grid only has room for the episode numbers
+        if let (View::Episode, Some((_, list))) = (&self.view, &self.resp.episode_list)
+            && list.len() > GRID_MIN_EPISODES
+            && let Some(index) = self
+                .table_state
+                .selected()
+                .and_then(|row| self.rows_to_data_index.get(row))
+        {
+            let episode = list[*index].as_str();
+            let info = show.episode_infos.get(episode);
+            let title = match info.and_then(|i| i.notes.as_deref()) {
+                Some(title) => format!("Episode {}: {}", episode, title),
+                None => format!("Episode {}", episode),
+            };
+            lines.push(line![span!(Style::new().magenta().bold(); title)]);
+            if let Some(aired) = info.and_then(|i| i.upload_date(self.mode.as_str())) {
+                lines.push(field("Aired: ", aired.to_string()));
+            }
+            if let Some(minutes) = info.and_then(|i| i.duration_minutes()) {
+                lines.push(field("Duration: ", format!("{} min", minutes)));
+            }
+            lines.push(Line::default());
         }
 
-        let header = Row::new(vec![
-            Line::from("Episodes").alignment(HorizontalAlignment::Center),
-        ])
-        .style(Style::default().bold().yellow())
-        .bottom_margin(1);
+        lines.push(line![
+            span!(Style::new().magenta().bold(); show.name.clone())
+        ]);
+        if let Some(english_name) = show.english_name.as_ref().filter(|n| **n != show.name) {
+            lines.push(line![
+                span!(Style::new().red().bold(); english_name.clone())
+            ]);
+        }
+        lines.push(Line::default());
 
-        frame.render_stateful_widget(
-            Table::new(rows, [Constraint::Fill(1)])
-                .header(header)
-                .style(Style::new().fg(Color::Cyan))
-                .highlight_symbol(self.select_icon.to_string())
-                .row_highlight_style(Style::new().bg(Color::LightCyan).fg(Color::Black))
-                .block(Block::bordered().border_type(BorderType::Rounded)),
+        let alt_names: Vec<&str> = show
+            .native_name
+            .iter()
+            .chain(show.alt_names.iter().flatten())
+            .map(String::as_str)
+            .collect();
+        if !alt_names.is_empty() {
+            lines.push(field("Also known as: ", alt_names.join(", ")));
+        }
+        if let Some(status) = &show.status {
+            lines.push(field("Status: ", status.clone()));
+        }
+        if let Some(score) = show.score {
+            lines.push(field("Score: ", format!("{:.2}", score)));
+        }
+        if let Some(season) = &show.season {
+            let quarter = season.quarter.as_deref().unwrap_or_default();
+            let year = season.year.map(|y| y.to_string()).unwrap_or_default();
+            lines.push(field("Season: ", format!("{} {}", quarter, year)));
+        }
+        if let Some(minutes) = show.duration_minutes() {
+            lines.push(field("Duration: ", format!("{} min", minutes)));
+        }
+        if let Some(genres) = show.genres.as_ref().filter(|g| !g.is_empty()) {
+            lines.push(field("Genres: ", genres.join(", ")));
+        }
+        if let Some(studios) = show.studios.as_ref().filter(|s| !s.is_empty()) {
+            lines.push(field("Studios: ", studios.join(", ")));
+        }
+        if let Some(aired) = show.aired_start.and_then(|d| d.to_unix()) {
+            lines.push(field("Aired: ", format_unix(aired)[..10].to_string()));
+        }
+        if let Some(next) = show.next_episode_at(self.mode.as_str()) {
+            lines.push(field(
+                "Next episode: ",
+                format!("~{} UTC", format_unix(next)),
+            ));
+        }
+
+        if let Some(description) = &show.description {
+            lines.push(Line::default());
+            lines.push(Line::from(description.as_str()));
+        }
+
+        frame.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .style(Style::new().cyan()),
+            ),
             area,
-            &mut self.table_state,
         );
     }
 
-    fn render_episode_providers(&mut self, frame: &mut Frame, area: Rect) {
-        let Some((_, links_list)) = &self.resp.episode_provider_list else {
-            return;
-        };
+    fn render_info_screen(&mut self, frame: &mut Frame, area: Rect) {
+        if let (View::Episode | View::Provider | View::Related, Some((show, _))) =
+            (&self.view, &self.resp.episode_list)
+        {
+            let thumbnail = self.thumbnail.as_mut().filter(|t| {
+                matches!(self.view, View::Episode) && Some(&t.url) == self.thumbnail_url.as_ref()
+            });
 
-        let mut rows = Vec::new();
-        for index in &self.rows_to_data_index {
-            let (provider_name, _link) = &links_list[*index];
-            rows.push(
-                Row::new(vec![
-                    Line::styled(provider_name, Style::new().red().bold())
-                        .alignment(HorizontalAlignment::Center),
-                ])
-                .height(2),
-            );
+            match thumbnail {
+                Some(thumbnail) => {
+                    let [top, bottom] = vertical![==40%, *=1].areas(area);
+                    frame.render_widget(
+                        Block::bordered()
+                            .border_type(BorderType::Rounded)
+                            .style(Style::new().cyan()),
+                        top,
+                    );
+                    frame.render_stateful_widget(
+                        StatefulImage::default(),
+                        Block::bordered().inner(top),
+                        &mut thumbnail.protocol,
+                    );
+                    self.render_show_detail(frame, bottom, show);
+                }
+                None => self.render_show_detail(frame, area, show),
+            }
+            return;
         }
 
-        let header = Row::new(vec![
-            Line::from("Provider").alignment(HorizontalAlignment::Center),
-        ])
-        .style(Style::default().bold().yellow())
-        .bottom_margin(1);
-
-        frame.render_stateful_widget(
-            Table::new(rows, [Constraint::Fill(1)])
-                .header(header)
-                .style(Style::new().fg(Color::Cyan))
-                .highlight_symbol(self.select_icon.to_string())
-                .row_highlight_style(Style::new().bg(Color::LightCyan).fg(Color::Black))
-                .block(Block::bordered().border_type(BorderType::Rounded)),
-            area,
-            &mut self.table_state,
-        );
-    }
+        if matches!(self.view, View::Library) {
+            self.render_library_entry(frame, area);
+            return;
+        }
+        if matches!(self.view, View::Watchlist) {
+            self.render_followed(frame, area);
+            return;
+        }
+        if matches!(self.view, View::Schedule) {
+            return;
+        }
 
-    fn render_info_screen(&self, frame: &mut Frame, area: Rect) {
-        let [top, bottom] = vertical![==50%, *=1].areas(area);
+        let [_top, bottom] = vertical![==50%, *=1].areas(area);
 
         let Some(data) = &self.resp.search else {
             return;
@@ -581,18 +2924,150 @@ impl App {
             return;
         };
 
+        let description = data[*data_index].description.as_str();
+        let mut block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .style(Style::new().cyan());
+        if self.search_descriptions && !self.input.value().trim().is_empty() {
+            let count = count_matches(description, self.input.value());
+            block = block.title(format!(
+                "{} match{} in the description",
+                count,
+                if count == 1 { "" } else { "es" }
+            ));
+        }
+
         frame.render_widget(
-            Paragraph::new(line!(data[*data_index].description.as_str()))
+            Paragraph::new(line!(description))
                 .wrap(Wrap { trim: false })
-                .block(
-                    Block::bordered()
-                        .border_type(BorderType::Rounded)
-                        .style(Style::new().cyan()),
-                ),
+                .block(block),
             bottom,
         );
     }
 
+    /// pieces of the footer along with what clicking them does
+    fn footer_hints(&self) -> Vec<(Span<'static>, Option<Hint>)> {
+        vec![
+            (span!("move "), None),
+            (span!(Style::default().bold().yellow(); "Up/Down "), None),
+            (span!("using "), None),
+            (
+                span!(Style::default().bold().yellow(); "↑ / ctrl+k / ctrl+p "),
+                Some(Hint::Up),
+            ),
+            (span!("and "), None),
+            (
+                span!(Style::default().bold().yellow(); "↓ / ctrl+j / ctrl+n "),
+                Some(Hint::Down),
+            ),
+            (span!("keys, "), None),
+            (span!("press "), None),
+            (
+                span!(Style::default().bold().green(); "Enter "),
+                Some(Hint::Select),
+            ),
+            (span!("to "), None),
+            (
+                span!(Style::default().bold().green(); "Select "),
+                Some(Hint::Select),
+            ),
+            (span!("and "), None),
+            (
+                span!(Style::default().bold().cyan(); "ctrl+<BS> "),
+                Some(Hint::Back),
+            ),
+            (span!("to go "), None),
+            (
+                span!(Style::default().bold().cyan(); "Back"),
+                Some(Hint::Back),
+            ),
+            (span!(", "), None),
+            (
+                span!(Style::default().bold().magenta(); "Tab "),
+                Some(Hint::Tab),
+            ),
+            (span!("to switch "), None),
+            (
+                span!(Style::default().bold().magenta(); "Tabs"),
+                Some(Hint::Tab),
+            ),
+        ]
+    }
+
+    /// views that led to the current one, like `Search › Show › Ep 12 › Provider`
+    fn breadcrumb(&self) -> Line<'_> {
+        let views = self
+            .nav_stack
+            .iter()
+            .map(|state| (state.view, state.episode_list.as_ref()))
+            .chain(std::iter::once((
+                self.view,
+                self.resp.episode_list.as_ref(),
+            )));
+
+        let mut crumbs = Vec::new();
+        for (view, episode_list) in views {
+            match view {
+                View::Loading => (),
+                View::Search => crumbs.push("Search".to_string()),
+                View::Episode => crumbs.extend(episode_list.map(|(show, _)| show.name.clone())),
+                View::Provider => {
+                    if let Some((episode, _)) = &self.resp.episode_provider_list {
+                        crumbs.push(format!("Ep {}", episode));
+                    }
+                    crumbs.push("Provider".to_string());
+                }
+                View::Related => crumbs.push("Franchise".to_string()),
+                View::Library => crumbs.push("Library".to_string()),
+                View::Watchlist => crumbs.push("Watchlist".to_string()),
+                View::Schedule => crumbs.push("Schedule".to_string()),
+            }
+        }
+
+        let last = crumbs.len().saturating_sub(1);
+        let mut line = Line::default();
+        for (i, crumb) in crumbs.into_iter().enumerate() {
+            if i > 0 {
+                line.push_span(span!(Color::DarkGray; " › "));
+            }
+            if i == last {
+                line.push_span(span!(Style::new().cyan().bold(); crumb));
+            } else {
+                line.push_span(span!(Color::Cyan; crumb));
+            }
+        }
+        line
+    }
+
+    /// mode, requests in flight, whether the api is reachable and the last error
+    fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        let pending = PENDING.load(Ordering::Relaxed);
+        let mut line = line![
+            span!(Style::new().magenta().bold(); self.mode.as_str()),
+            span!(Color::DarkGray; " │ "),
+            if pending == 0 {
+                span!(Color::DarkGray; "idle")
+            } else {
+                span!(Color::Yellow; "{} pending", pending)
+            },
+            span!(Color::DarkGray; " │ "),
+            if self.offline {
+                span!(Color::Red; "offline")
+            } else {
+                span!(Color::Green; "online")
+            },
+        ];
+        if self.cached {
+            line.push_span(span!(Color::DarkGray; " │ "));
+            line.push_span(span!(Color::Yellow; "cached"));
+        }
+        if let Some(error) = &self.last_error {
+            line.push_span(span!(Color::DarkGray; " │ "));
+            line.push_span(span!(Color::Red; error));
+        }
+        frame.render_widget(Paragraph::new(line), area);
+    }
+
     fn render_footer(&self, frame: &mut Frame, area: Rect, line: Line) {
         frame.render_widget(
             Paragraph::new(line)
@@ -607,18 +3082,28 @@ impl App {
     }
 
     fn render(&mut self, frame: &mut Frame) {
-        let [top, middle, bottom] = vertical![==3, *=1, ==3].areas(frame.area());
+        let [top, middle, bottom, status] = vertical![==3, *=1, ==3, ==1].areas(frame.area());
         let [middle_l, middle_r] = horizontal![==60%, *=1].areas(middle);
 
         self.render_search_input(frame, top);
 
         self.render_info_screen(frame, middle_r);
 
+        self.table_area = middle_l;
+        self.grid_columns = 0;
         match self.view {
+            View::Loading if self.searching => {
+                let [tabs, list] = vertical![==3, *=1].areas(middle_l);
+                self.render_search_tabs(frame, tabs);
+                self.render_skeleton(frame, list)
+            }
             View::Loading => self.render_skeleton(frame, middle_l),
             View::Search => {
+                let [tabs, list] = vertical![==3, *=1].areas(middle_l);
+                self.table_area = list;
+                self.render_search_tabs(frame, tabs);
                 if self.resp.search.is_some() {
-                    self.render_search_result(frame, middle_l);
+                    self.render_search_result(frame, list);
                 }
             }
             View::Episode => {
@@ -631,36 +3116,272 @@ impl App {
                     self.render_episode_providers(frame, middle_l);
                 }
             }
+            View::Related => {
+                if self.resp.franchise.is_some() {
+                    self.render_franchise(frame, middle_l);
+                }
+            }
+            View::Library => self.render_library(frame, middle_l),
+            View::Watchlist => self.render_watchlist(frame, middle_l),
+            View::Schedule => {
+                if self.resp.schedule.is_some() {
+                    self.render_schedule(frame, middle_l);
+                }
+            }
         }
 
-        self.render_footer(
-            frame,
-            bottom,
-            line![
-                "move ",
-                span!(Style::default().bold().yellow(); "Up/Down "),
-                "using ",
-                span!(Style::default().bold().yellow(); "↑ / ctrl+k / ctrl+p "),
-                "and ",
-                span!(Style::default().bold().yellow(); "↓ / ctrl+j / ctrl+n "),
-                "keys, ",
-                "press ",
-                span!(Style::default().bold().green(); "Enter "),
-                "to ",
-                span!(Style::default().bold().green(); "Select "),
-                "and ",
-                span!(Style::default().bold().cyan(); "ctrl+<BS> "),
-                "to go ",
-                span!(Style::default().bold().cyan(); "Back")
-            ],
-        );
+        self.footer_area = bottom;
+        let footer = Line::from_iter(self.footer_hints().into_iter().map(|(span, _)| span));
+        self.render_footer(frame, bottom, footer);
+        self.render_status_bar(frame, status);
     }
 }
 
 fn main() -> color_eyre::eyre::Result<()> {
     color_eyre::install()?;
 
-    let mut app = App::new();
-    ratatui::run(|terminal| app.main_loop(terminal))?;
+    let args = Args::parse();
+    if let Some(command) = args.command {
+        return match command {
+            SubCommand::Check { json } => check(args.debug, json),
+            SubCommand::Schedule { json } => print_schedule(args.debug, json),
+            SubCommand::Tracker {
+                action: TrackerAction::Sync,
+            } => tracker_sync(args.debug),
+        };
+    }
+
+    let mut app = App::new(args)?;
+    ratatui::run(|terminal| {
+        execute!(std::io::stdout(), EnableMouseCapture)?;
+        let result = app.main_loop(terminal);
+        execute!(std::io::stdout(), DisableMouseCapture)?;
+        result
+    })?;
+
+    if let Some(printed) = app.printed {
+        println!("{}", printed);
+    }
+    Ok(())
+}
+
+/// new episodes of a followed show found by `sho check`
+#[derive(Serialize, Debug)]
+struct NewEpisodes {
+    id: String,
+    name: String,
+    mode: Mode,
+    episodes: Vec<String>,
+}
+
+/// refresh the episode list of every followed show and report the new episodes
+fn check(debug: bool, json: bool) -> color_eyre::eyre::Result<()> {
+    let api = Api::new(debug);
+    let mut watchlist = Watchlist::load(Watchlist::path()).map_err(|e| eyre!("{}", e))?;
+
+    let followed: Vec<(String, Mode)> = watchlist
+        .shows
+        .iter()
+        .map(|(id, show)| (id.clone(), show.mode))
+        .collect();
+
+    let mut found = Vec::new();
+    for (id, mode) in followed {
+        let (show, episodes) = match api.get_episode_list(&id, mode) {
+            Ok(list) => list,
+            Err(e) => {
+                eprintln!("Error checking {}: {}", id, e);
+                continue;
+            }
+        };
+        let episodes = watchlist.update(&id, &episodes);
+        if !episodes.is_empty() {
+            found.push(NewEpisodes {
+                id,
+                name: show.name,
+                mode,
+                episodes,
+            });
+        }
+    }
+    watchlist.save().map_err(|e| eyre!("{}", e))?;
+
+    if json {
+        // one object per line, so runs can be appended to the same file
+        for show in &found {
+            println!("{}", serde_json::to_string(show)?);
+        }
+    } else {
+        for show in &found {
+            println!(
+                "{} ({}): {}",
+                show.name,
+                show.mode.as_str(),
+                show.episodes.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// print when the followed shows air next
+fn print_schedule(debug: bool, json: bool) -> color_eyre::eyre::Result<()> {
+    let api = Api::new(debug);
+    let watchlist = Watchlist::load(Watchlist::path()).map_err(|e| eyre!("{}", e))?;
+    let ids: Vec<String> = watchlist.shows.keys().cloned().collect();
+    let shows = api.get_airing(&ids).map_err(|e| eyre!("{}", e))?;
+    let airings = schedule::airings(&watchlist.shows, &shows, unix_now());
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&airings)?);
+        return Ok(());
+    }
+
+    let mut previous_day = None;
+    for airing in &airings {
+        if previous_day != Some(airing.weekday) {
+            println!("{}", airing.weekday);
+            previous_day = Some(airing.weekday);
+        }
+        let time = if airing.due {
+            "due now  ".to_string()
+        } else {
+            format!("{} UTC", &format_unix(airing.at)[11..])
+        };
+        let episode = airing
+            .episode
+            .as_ref()
+            .map(|ep| format!(" episode {}", ep))
+            .unwrap_or_default();
+        println!(
+            "  {}  {} ({}){}",
+            time,
+            airing.name,
+            airing.mode.as_str(),
+            episode
+        );
+    }
+    Ok(())
+}
+
+/// push every finished episode to the trackers, looking up missing tracker ids
+fn tracker_sync(debug: bool) -> color_eyre::eyre::Result<()> {
+    let config = config::Config::load().map_err(|e| eyre!("{}", e))?;
+    let trackers = Tracker::from_config(&config);
+    if trackers.is_empty() {
+        return Err(eyre!(
+            "no tracker configured, set anilist_token or mal_token in {}",
+            config::Config::path().map_or("config.json".into(), |p| p.display().to_string())
+        ));
+    }
+
+    let api = Api::new(debug);
+    let mut history = History::load(History::path()).map_err(|e| eyre!("{}", e))?;
+    for (show_id, show) in history.shows.iter_mut() {
+        if show.anilist_id.is_none() && show.mal_id.is_none() {
+            match api.get_tracker_ids(show_id) {
+                Ok((anilist_id, mal_id)) => (show.anilist_id, show.mal_id) = (anilist_id, mal_id),
+                Err(e) => eprintln!("Error getting tracker ids of {}: {}", show.name, e),
+            }
+        }
+
+        for (tracker, result) in tracker::push(&trackers, show) {
+            let service = tracker.service.name();
+            match result {
+                Ok(true) => println!("{}: {} -> episode {}", service, show.name, show.episode),
+                Ok(false) => println!("{}: {} is up to date", service, show.name),
+                Err(e) => eprintln!("{}: {} failed: {}", service, show.name, e),
+            }
+        }
+    }
+    history.save().map_err(|e| eyre!("{}", e))?;
+
     Ok(())
 }
+
+/// human readable byte count
+fn format_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if size < 1024.0 {
+            return format!("{:.1} {}", size, unit);
+        }
+        size /= 1024.0;
+    }
+    format!("{:.1} GiB", size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_selected_when_the_filter_leaves_no_rows() {
+        assert_eq!(selected_index(&[], Some(0)), None);
+        assert_eq!(selected_index(&[4, 2], Some(1)), Some(2));
+        assert_eq!(selected_index(&[4, 2], Some(2)), None);
+        assert_eq!(selected_index(&[4, 2], None), None);
+    }
+
+    #[test]
+    fn highlight_splits_matched_runs() {
+        let style = Style::new().magenta();
+        let matched = style.patch(Style::new().light_yellow().underlined());
+        assert_eq!(
+            highlight("Frieren", &[0, 1, 4], style),
+            [
+                Span::styled("Fr", matched),
+                Span::styled("ie", style),
+                Span::styled("r", matched),
+                Span::styled("en", style),
+            ]
+        );
+        assert_eq!(
+            highlight("Frieren", &[], style),
+            [Span::styled("Frieren", style)]
+        );
+    }
+
+    #[test]
+    fn highlight_counts_chars_like_the_matcher() {
+        // an e with a combining accent, one grapheme of two chars
+        let text = "e\u{301}te";
+        let pattern = Pattern::new(
+            "t",
+            CaseMatching::Smart,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+        );
+        let indices = match_indices(&pattern, &mut Matcher::new(Config::DEFAULT), text);
+        assert_eq!(indices, [2]);
+
+        let style = Style::new();
+        let matched = style.patch(Style::new().light_yellow().underlined());
+        assert_eq!(
+            highlight(text, &indices, style),
+            [
+                Span::styled("e\u{301}", style),
+                Span::styled("t", matched),
+                Span::styled("e", style),
+            ]
+        );
+
+        // an escaped space matches the one joining the two names
+        let pattern = Pattern::new(
+            "n\\ F",
+            CaseMatching::Smart,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+        );
+        let text = "Frieren Frieren: Beyond";
+        let indices = match_indices(&pattern, &mut Matcher::new(Config::DEFAULT), text);
+        assert_eq!(indices, [6, 7, 8]);
+        assert_eq!(split_indices(&indices, 7), (vec![6], vec![0]));
+    }
+}
@@ -0,0 +1,261 @@
+//! Built-in player presets selectable with `--player`
+
+use clap::ValueEnum;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    Mpv,
+    Vlc,
+    Iina,
+    Mplayer,
+    Celluloid,
+    /// syncplay with its default mpv backend
+    Syncplay,
+    /// print the stream url and exit instead of playing it
+    Print,
+}
+
+impl Player {
+    /// Executable looked up in `PATH`, `None` when nothing is launched
+    pub fn binary(&self) -> Option<&'static str> {
+        match self {
+            Player::Mpv => Some("mpv"),
+            Player::Vlc => Some("vlc"),
+            Player::Iina => Some("iina"),
+            Player::Mplayer => Some("mplayer"),
+            Player::Celluloid => Some("celluloid"),
+            Player::Syncplay => Some("syncplay"),
+            Player::Print => None,
+        }
+    }
+
+    /// Command in `SHO_PLAYER_CMD` syntax, the subtitle flag is only added
+    /// when the source has a subtitle track
+    pub fn template(&self, with_subtitle: bool) -> String {
+        let (cmd, subtitle_flag) = match self {
+            Player::Mpv => (
+                "mpv --force-media-title='{title} - {episode}' --user-agent='{user_agent}' --http-header-fields='Referer: {referer}' --start={start}",
+                "--sub-file='{subtitle}'",
+            ),
+            Player::Vlc => (
+                "vlc --meta-title='{title} - {episode}' --http-user-agent='{user_agent}' --http-referrer='{referer}' --start-time={start}",
+                "--input-slave='{subtitle}'",
+            ),
+            Player::Iina => (
+                "iina --no-stdin --mpv-force-media-title='{title} - {episode}' --mpv-user-agent='{user_agent}' --mpv-http-header-fields='Referer: {referer}' --mpv-start={start}",
+                "--mpv-sub-file='{subtitle}'",
+            ),
+            Player::Mplayer => (
+                "mplayer -title '{title} - {episode}' -user-agent '{user_agent}' -referrer '{referer}' -ss {start}",
+                "-sub '{subtitle}'",
+            ),
+            Player::Celluloid => (
+                "celluloid --mpv-force-media-title='{title} - {episode}' --mpv-user-agent='{user_agent}' --mpv-http-header-fields='Referer: {referer}' --mpv-start={start}",
+                "--mpv-sub-file='{subtitle}'",
+            ),
+            // player arguments go after `--`, so the url has to come first
+            Player::Syncplay => {
+                let mut cmd = "syncplay '{url}' -- --force-media-title='{title} - {episode}' --user-agent='{user_agent}' --http-header-fields='Referer: {referer}'".to_string();
+                if with_subtitle {
+                    cmd.push_str(" --sub-file='{subtitle}'");
+                }
+                return cmd;
+            }
+            Player::Print => return "{url}".to_string(),
+        };
+
+        // left out for iina-cli, `launch` refuses urls that look like options anyway
+        let end = if *self == Player::Iina { "" } else { " --" };
+        if with_subtitle {
+            format!("{} {}{} '{{url}}'", cmd, subtitle_flag, end)
+        } else {
+            format!("{}{} '{{url}}'", cmd, end)
+        }
+    }
+}
+
+/// Full path of `binary` if it is in one of the `PATH` directories
+pub fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    find_in_dirs(binary, env::split_paths(&path))
+}
+
+fn find_in_dirs(binary: &str, mut dirs: impl Iterator<Item = PathBuf>) -> Option<PathBuf> {
+    dirs.find_map(|dir| {
+        let candidate = dir.join(binary);
+        if is_executable(&candidate) {
+            return Some(candidate);
+        }
+        // windows
+        let candidate = candidate.with_extension("exe");
+        is_executable(&candidate).then_some(candidate)
+    })
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(not(unix))]
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::{PlayerContext, render_args};
+    use std::fs;
+
+    const URL: &str = "https://example.com/ep.mp4";
+    const SUB: &str = "https://example.com/en.vtt";
+
+    fn ctx(subtitle: &str) -> PlayerContext {
+        PlayerContext {
+            url: URL.to_string(),
+            referer: "https://allmanga.to".to_string(),
+            user_agent: "Firefox".to_string(),
+            subtitle: subtitle.to_string(),
+            title: "Frieren".to_string(),
+            episode: "12".to_string(),
+            start: 90,
+            ..Default::default()
+        }
+    }
+
+    /// Player, arguments before the subtitle, subtitle arguments, arguments after it
+    type Case = (
+        Player,
+        &'static [&'static str],
+        &'static [&'static str],
+        &'static [&'static str],
+    );
+
+    #[test]
+    fn presets_render_to_argv() {
+        let table: [Case; 7] = [
+            (
+                Player::Mpv,
+                &[
+                    "mpv",
+                    "--force-media-title=Frieren - 12",
+                    "--user-agent=Firefox",
+                    "--http-header-fields=Referer: https://allmanga.to",
+                    "--start=90",
+                ],
+                &["--sub-file=https://example.com/en.vtt"],
+                &["--", URL],
+            ),
+            (
+                Player::Vlc,
+                &[
+                    "vlc",
+                    "--meta-title=Frieren - 12",
+                    "--http-user-agent=Firefox",
+                    "--http-referrer=https://allmanga.to",
+                    "--start-time=90",
+                ],
+                &["--input-slave=https://example.com/en.vtt"],
+                &["--", URL],
+            ),
+            (
+                Player::Iina,
+                &[
+                    "iina",
+                    "--no-stdin",
+                    "--mpv-force-media-title=Frieren - 12",
+                    "--mpv-user-agent=Firefox",
+                    "--mpv-http-header-fields=Referer: https://allmanga.to",
+                    "--mpv-start=90",
+                ],
+                &["--mpv-sub-file=https://example.com/en.vtt"],
+                &[URL],
+            ),
+            (
+                Player::Mplayer,
+                &[
+                    "mplayer",
+                    "-title",
+                    "Frieren - 12",
+                    "-user-agent",
+                    "Firefox",
+                    "-referrer",
+                    "https://allmanga.to",
+                    "-ss",
+                    "90",
+                ],
+                &["-sub", "https://example.com/en.vtt"],
+                &["--", URL],
+            ),
+            (
+                Player::Celluloid,
+                &[
+                    "celluloid",
+                    "--mpv-force-media-title=Frieren - 12",
+                    "--mpv-user-agent=Firefox",
+                    "--mpv-http-header-fields=Referer: https://allmanga.to",
+                    "--mpv-start=90",
+                ],
+                &["--mpv-sub-file=https://example.com/en.vtt"],
+                &["--", URL],
+            ),
+            (
+                Player::Syncplay,
+                &[
+                    "syncplay",
+                    URL,
+                    "--",
+                    "--force-media-title=Frieren - 12",
+                    "--user-agent=Firefox",
+                    "--http-header-fields=Referer: https://allmanga.to",
+                ],
+                &["--sub-file=https://example.com/en.vtt"],
+                &[],
+            ),
+            (Player::Print, &[URL], &[], &[]),
+        ];
+
+        for (player, before, subtitle, after) in table {
+            let without = render_args(&player.template(false), &ctx("")).unwrap();
+            let expected: Vec<&str> = before.iter().chain(after).copied().collect();
+            assert_eq!(without, expected, "{:?} without subtitle", player);
+
+            let with = render_args(&player.template(true), &ctx(SUB)).unwrap();
+            let expected: Vec<&str> = [before, subtitle, after].concat();
+            assert_eq!(with, expected, "{:?} with subtitle", player);
+        }
+    }
+
+    #[test]
+    fn finds_executables_in_the_first_directory_that_has_them() {
+        let root = env::temp_dir().join(format!("sho-player-{}", std::process::id()));
+        let (first, second) = (root.join("first"), root.join("second"));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+
+        // not executable, skipped on unix
+        fs::write(first.join("mpv"), "").unwrap();
+        let binary = second.join("mpv");
+        fs::write(&binary, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&binary, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let dirs = || [first.clone(), second.clone()].into_iter();
+        #[cfg(unix)]
+        assert_eq!(find_in_dirs("mpv", dirs()), Some(binary));
+        assert_eq!(find_in_dirs("vlc", dirs()), None);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+//! Expected airing times of the followed shows
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::{
+    api::{Mode, ShowDetail},
+    utils::weekday,
+    watchlist::Followed,
+};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Airing {
+    pub id: String,
+    pub name: String,
+    pub mode: Mode,
+    /// episode expected next
+    pub episode: Option<String>,
+    /// unix time the episode is expected at
+    pub at: i64,
+    /// day of the week of `at`, in UTC
+    pub weekday: &'static str,
+    /// the episode should be out already
+    pub due: bool,
+}
+
+/// Next episode of every releasing show of `shows` that is followed, soonest first
+pub fn airings(
+    followed: &BTreeMap<String, Followed>,
+    shows: &[ShowDetail],
+    now: i64,
+) -> Vec<Airing> {
+    let mut airings: Vec<Airing> = shows
+        .iter()
+        .filter_map(|show| {
+            let mode = followed.get(&show.id)?.mode;
+            let at = show.next_episode_at(mode.as_str())?;
+            let latest = show
+                .available_episodes_detail
+                .get(mode.as_str())
+                .into_iter()
+                .flatten()
+                .filter_map(|ep| ep.parse::<f64>().ok())
+                .reduce(f64::max);
+
+            Some(Airing {
+                id: show.id.clone(),
+                name: show.name.clone(),
+                mode,
+                episode: latest.map(|ep| (ep.floor() as u64 + 1).to_string()),
+                at,
+                weekday: weekday(at),
+                due: at < now,
+            })
+        })
+        .collect();
+
+    airings.sort_by_key(|airing| airing.at);
+    airings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn show(id: &str, status: &str, last: &str, episodes: &[&str]) -> ShowDetail {
+        serde_json::from_value(serde_json::json!({
+            "_id": id,
+            "name": id.to_uppercase(),
+            "status": status,
+            // a week in milliseconds
+            "broadcastInterval": "604800000",
+            "lastEpisodeDate": {
+                "dub": serde_json::from_str::<serde_json::Value>(last).unwrap()
+            },
+            "availableEpisodesDetail": { "dub": episodes },
+        }))
+        .unwrap()
+    }
+
+    fn followed(ids: &[&str]) -> BTreeMap<String, Followed> {
+        ids.iter()
+            .map(|id| {
+                (
+                    id.to_string(),
+                    Followed {
+                        name: id.to_string(),
+                        mode: Mode::Dub,
+                        episodes: Vec::new(),
+                        unseen: Vec::new(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lists_followed_releasing_shows_soonest_first() {
+        let shows = [
+            // 2024-06-01 12:00 UTC, next on saturday the 8th
+            show(
+                "later",
+                "Releasing",
+                r#"{"year":2024,"month":6,"date":1,"hour":12,"minute":0}"#,
+                &["1", "2", "10"],
+            ),
+            // 2024-05-27 18:30 UTC, next on monday the 3rd
+            show(
+                "sooner",
+                "Releasing",
+                r#"{"year":2024,"month":5,"date":27,"hour":18,"minute":30}"#,
+                &["3", "3.5"],
+            ),
+            show(
+                "finished",
+                "Finished",
+                r#"{"year":2024,"month":5,"date":27,"hour":18,"minute":30}"#,
+                &["12"],
+            ),
+            show(
+                "not-followed",
+                "Releasing",
+                r#"{"year":2024,"month":5,"date":27,"hour":18,"minute":30}"#,
+                &["1"],
+            ),
+        ];
+        // 2024-06-05 00:00 UTC
+        let now = 1717545600;
+
+        let airings = airings(&followed(&["later", "sooner", "finished"]), &shows, now);
+        let summary: Vec<_> = airings
+            .iter()
+            .map(|a| (a.id.as_str(), a.weekday, a.episode.as_deref(), a.due))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("sooner", "Monday", Some("4"), true),
+                ("later", "Saturday", Some("11"), false),
+            ]
+        );
+    }
+}
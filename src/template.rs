@@ -0,0 +1,190 @@
+//! Placeholder substitution for `SHO_PLAYER_CMD`
+
+/// Values that can be used as `{placeholder}` in the player command
+#[derive(Debug, Default, Clone)]
+pub struct PlayerContext {
+    /// url of the video
+    pub url: String,
+    pub referer: String,
+    pub user_agent: String,
+    /// url of the external subtitle track, empty when there is none
+    pub subtitle: String,
+    /// name of the show
+    pub title: String,
+    pub show_id: String,
+    /// episode string such as `12` or `12.5`
+    pub episode: String,
+    /// sub, dub or raw
+    pub mode: String,
+    /// name of the source the url came from
+    pub provider: String,
+    /// resolution reported by the source such as `1080p`, empty when unknown
+    pub quality: String,
+    /// position to resume from, in seconds
+    pub start: u64,
+}
+
+impl PlayerContext {
+    /// Value of the placeholder `name`, `None` for unknown placeholders
+    pub fn value(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "url" => &self.url,
+            "referer" => &self.referer,
+            "user_agent" => &self.user_agent,
+            "subtitle" => &self.subtitle,
+            "title" => &self.title,
+            "show_id" => &self.show_id,
+            "episode" => &self.episode,
+            "mode" => &self.mode,
+            "provider" => &self.provider,
+            "quality" => &self.quality,
+            "start" => return Some(self.start.to_string()),
+            _ => return None,
+        };
+        Some(value.clone())
+    }
+}
+
+/// Replace every known `{placeholder}` of `template` with its value.
+///
+/// Substitution happens in a single pass, so values containing braces are
+/// never expanded again. Unknown placeholders are kept as they are.
+pub fn render(template: &str, ctx: &PlayerContext) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open..];
+
+        let value = rest
+            .find('}')
+            .and_then(|close| Some((close, ctx.value(&rest[1..close])?)));
+
+        match value {
+            Some((close, value)) => {
+                out.push_str(&value);
+                rest = &rest[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+
+    out
+}
+
+/// Split `template` into arguments with shell quoting rules, then fill the
+/// placeholders of each argument. Values can't break out of their argument.
+pub fn render_args(
+    template: &str,
+    ctx: &PlayerContext,
+) -> Result<Vec<String>, shell_words::ParseError> {
+    Ok(shell_words::split(template)?
+        .iter()
+        .map(|arg| render(arg, ctx))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> PlayerContext {
+        PlayerContext {
+            url: "https://example.com/ep.mp4".to_string(),
+            referer: "https://allmanga.to".to_string(),
+            user_agent: "Firefox".to_string(),
+            title: "Frieren".to_string(),
+            show_id: "abc123".to_string(),
+            episode: "12".to_string(),
+            mode: "sub".to_string(),
+            provider: "Default".to_string(),
+            quality: "1080p".to_string(),
+            start: 90,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn substitutes_every_placeholder() {
+        let cmd = render(
+            "mpv --force-media-title='{title} - {episode}' --start={start} '{url}' # {show_id} {mode} {provider} {quality}",
+            &ctx(),
+        );
+        assert_eq!(
+            cmd,
+            "mpv --force-media-title='Frieren - 12' --start=90 'https://example.com/ep.mp4' # abc123 sub Default 1080p"
+        );
+    }
+
+    #[test]
+    fn substitutes_repeated_placeholders() {
+        assert_eq!(render("{episode}/{episode}", &ctx()), "12/12");
+    }
+
+    #[test]
+    fn keeps_unknown_placeholders_and_stray_braces() {
+        assert_eq!(render("{nope} { {url", &ctx()), "{nope} { {url".to_string());
+        assert_eq!(
+            render("{}{{url}}", &ctx()),
+            "{}{https://example.com/ep.mp4}"
+        );
+    }
+
+    #[test]
+    fn does_not_expand_placeholders_inside_values() {
+        let ctx = PlayerContext {
+            title: "{url}".to_string(),
+            ..ctx()
+        };
+        assert_eq!(render("{title}", &ctx), "{url}");
+    }
+
+    #[test]
+    fn splits_arguments_before_substituting() {
+        let ctx = PlayerContext {
+            url: "https://example.com/a'b;rm -rf ~ $(id).mp4".to_string(),
+            ..ctx()
+        };
+        let argv = render_args(
+            "mpv --http-header-fields='Referer: {referer}' --force-media-title=\"{title} {episode}\" '{url}'",
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(
+            argv,
+            [
+                "mpv",
+                "--http-header-fields=Referer: https://allmanga.to",
+                "--force-media-title=Frieren 12",
+                "https://example.com/a'b;rm -rf ~ $(id).mp4",
+            ]
+        );
+    }
+
+    #[test]
+    fn unquoted_placeholder_stays_one_argument() {
+        let ctx = PlayerContext {
+            title: "two words".to_string(),
+            ..ctx()
+        };
+        assert_eq!(
+            render_args("vlc {title}", &ctx).unwrap(),
+            ["vlc", "two words"]
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_quotes() {
+        assert!(render_args("mpv '{url}", &ctx()).is_err());
+    }
+
+    #[test]
+    fn empty_subtitle_renders_empty() {
+        assert_eq!(render("--sub-file='{subtitle}'", &ctx()), "--sub-file=''");
+    }
+}
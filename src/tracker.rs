@@ -0,0 +1,411 @@
+//! Episode progress pushed to AniList and MyAnimeList
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::{collections::BTreeMap, fs, io, path::PathBuf, time::Duration};
+use ureq::Agent;
+
+use crate::{config::Config, utils::data_dir};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    AniList,
+    Mal,
+}
+
+impl Service {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Service::AniList => "AniList",
+            Service::Mal => "MyAnimeList",
+        }
+    }
+
+    fn endpoint(&self) -> &'static str {
+        match self {
+            Service::AniList => "https://graphql.anilist.co",
+            Service::Mal => "https://api.myanimelist.net/v2",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Tracker {
+    pub service: Service,
+    /// oauth access token
+    token: String,
+    endpoint: String,
+    agent: Agent,
+}
+
+impl Tracker {
+    pub fn new(service: Service, token: &str) -> Self {
+        let config = Agent::config_builder()
+            .timeout_per_call(Some(Duration::from_secs(12)))
+            .build();
+
+        Tracker {
+            service,
+            token: token.to_string(),
+            endpoint: service.endpoint().to_string(),
+            agent: Agent::new_with_config(config),
+        }
+    }
+
+    /// Talk to `endpoint` instead of the real service
+    #[cfg(test)]
+    fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Trackers that have a token in the config
+    pub fn from_config(config: &Config) -> Vec<Self> {
+        [
+            (Service::AniList, &config.anilist_token),
+            (Service::Mal, &config.mal_token),
+        ]
+        .into_iter()
+        .filter_map(|(service, token)| Some(Tracker::new(service, token.as_deref()?)))
+        .collect()
+    }
+
+    /// Id of `show` on this tracker
+    pub fn media_id(&self, show: &Watched) -> Option<u64> {
+        match self.service {
+            Service::AniList => show.anilist_id,
+            Service::Mal => show.mal_id,
+        }
+    }
+
+    fn bearer(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+
+    fn anilist(&self, query: &str, variables: Value) -> Result<Value, Box<dyn std::error::Error>> {
+        let resp: Value = self
+            .agent
+            .post(&self.endpoint)
+            .header("Authorization", self.bearer())
+            .header("Accept", "application/json")
+            .send_json(json!({ "query": query, "variables": variables }))?
+            .into_body()
+            .read_json()?;
+
+        if let Some(error) = resp["errors"].get(0) {
+            return Err(format!("AniList: {}", error["message"]).into());
+        }
+        Ok(resp["data"].clone())
+    }
+
+    /// Episodes watched according to the tracker, `None` when the show is not on the list
+    pub fn progress(&self, media_id: u64) -> Result<Option<u32>, Box<dyn std::error::Error>> {
+        let progress = match self.service {
+            Service::AniList => {
+                let data = self.anilist(
+                    "query ($mediaId: Int) { Media(id: $mediaId) { mediaListEntry { progress } } }",
+                    json!({ "mediaId": media_id }),
+                )?;
+                data["Media"]["mediaListEntry"]["progress"].as_u64()
+            }
+            Service::Mal => {
+                let resp: Value = self
+                    .agent
+                    .get(format!("{}/anime/{}", self.endpoint, media_id))
+                    .query("fields", "my_list_status")
+                    .header("Authorization", self.bearer())
+                    .call()?
+                    .into_body()
+                    .read_json()?;
+                resp["my_list_status"]["num_episodes_watched"].as_u64()
+            }
+        };
+        Ok(progress.map(|p| p as u32))
+    }
+
+    /// Raise the progress of `media_id` to `episode`, true when the tracker was behind
+    pub fn update(&self, media_id: u64, episode: u32) -> Result<bool, Box<dyn std::error::Error>> {
+        if self.progress(media_id)?.is_some_and(|p| p >= episode) {
+            return Ok(false);
+        }
+
+        match self.service {
+            Service::AniList => {
+                self.anilist(
+                    "mutation ($mediaId: Int, $progress: Int) { SaveMediaListEntry(mediaId: $mediaId, progress: $progress, status: CURRENT) { progress } }",
+                    json!({ "mediaId": media_id, "progress": episode }),
+                )?;
+            }
+            Service::Mal => {
+                self.agent
+                    .patch(format!(
+                        "{}/anime/{}/my_list_status",
+                        self.endpoint, media_id
+                    ))
+                    .header("Authorization", self.bearer())
+                    .send_form([
+                        ("status", "watching"),
+                        ("num_watched_episodes", &episode.to_string()),
+                    ])?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Latest episode finished of a show
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Watched {
+    pub name: String,
+    pub anilist_id: Option<u64>,
+    pub mal_id: Option<u64>,
+    /// 0 until an episode with a number was finished
+    pub episode: u32,
+    /// every episode finished in sho, as listed by allanime
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub finished: Vec<String>,
+}
+
+/// Episodes finished in sho, kept to sync trackers that could not be reached
+#[derive(Debug, Default)]
+pub struct History {
+    path: Option<PathBuf>,
+    /// keyed by allanime show id
+    pub shows: BTreeMap<String, Watched>,
+}
+
+impl History {
+    pub fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("progress.json"))
+    }
+
+    /// Read the history, a missing file is an empty history
+    pub fn load(path: Option<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        let shows = match path.as_deref().map(fs::read_to_string) {
+            Some(Ok(content)) => serde_json::from_str(&content)?,
+            Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => BTreeMap::new(),
+        };
+        Ok(History { path, shows })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.shows)?)?;
+        Ok(())
+    }
+
+    /// Remember that `episode` of `show_id` was finished, keeping the highest episode
+    pub fn record(&mut self, show_id: &str, show: Watched) -> &Watched {
+        let entry = self.shows.entry(show_id.to_string()).or_default();
+        entry.episode = entry.episode.max(show.episode);
+        entry.name = show.name;
+        entry.anilist_id = show.anilist_id.or(entry.anilist_id);
+        entry.mal_id = show.mal_id.or(entry.mal_id);
+        for episode in show.finished {
+            if !entry.finished.contains(&episode) {
+                entry.finished.push(episode);
+            }
+        }
+        entry
+    }
+}
+
+/// Episode number counted by trackers, `12.5` counts as 12
+pub fn episode_number(episode: &str) -> Option<u32> {
+    episode.parse::<f64>().ok().map(|e| e as u32)
+}
+
+/// Outcome of an update, true when the tracker was behind
+pub type Pushed<'a> = (&'a Tracker, Result<bool, Box<dyn std::error::Error>>);
+
+/// Push `show` to every tracker that knows it, nothing before a numbered episode was finished
+pub fn push<'a>(trackers: &'a [Tracker], show: &Watched) -> Vec<Pushed<'a>> {
+    if show.episode == 0 {
+        return Vec::new();
+    }
+    trackers
+        .iter()
+        .filter_map(|tracker| {
+            let media_id = tracker.media_id(show)?;
+            Some((tracker, tracker.update(media_id, show.episode)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::mpsc, thread};
+
+    /// request seen by the mock tracker
+    #[derive(Debug)]
+    struct Seen {
+        method: String,
+        url: String,
+        authorization: String,
+        body: String,
+    }
+
+    /// Serve `responses` in order on a local port, handing back every request
+    fn mock_tracker(responses: Vec<&'static str>) -> (String, mpsc::Receiver<Seen>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", server.server_addr());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for response in responses {
+                let mut request = server.recv().unwrap();
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let authorization = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("Authorization"))
+                    .map(|h| h.value.to_string())
+                    .unwrap_or_default();
+                tx.send(Seen {
+                    method: request.method().to_string(),
+                    url: request.url().to_string(),
+                    authorization,
+                    body,
+                })
+                .unwrap();
+                request
+                    .respond(tiny_http::Response::from_string(response))
+                    .unwrap();
+            }
+        });
+
+        (endpoint, rx)
+    }
+
+    fn frieren(episode: u32) -> Watched {
+        Watched {
+            name: "Frieren".to_string(),
+            anilist_id: Some(154587),
+            mal_id: Some(52991),
+            episode,
+            finished: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn anilist_pushes_progress() {
+        let (endpoint, seen) = mock_tracker(vec![
+            r#"{"data":{"Media":{"mediaListEntry":{"progress":3}}}}"#,
+            r#"{"data":{"SaveMediaListEntry":{"progress":4}}}"#,
+        ]);
+        let tracker = Tracker::new(Service::AniList, "secret").with_endpoint(&endpoint);
+
+        assert!(tracker.update(154587, 4).unwrap());
+
+        let query = seen.recv().unwrap();
+        assert_eq!(query.method, "POST");
+        assert_eq!(query.authorization, "Bearer secret");
+        let save: Value = serde_json::from_str(&seen.recv().unwrap().body).unwrap();
+        assert!(
+            save["query"]
+                .as_str()
+                .unwrap()
+                .contains("SaveMediaListEntry")
+        );
+        assert_eq!(save["variables"], json!({"mediaId": 154587, "progress": 4}));
+    }
+
+    #[test]
+    fn anilist_keeps_higher_progress() {
+        let (endpoint, seen) = mock_tracker(vec![
+            r#"{"data":{"Media":{"mediaListEntry":{"progress":10}}}}"#,
+        ]);
+        let tracker = Tracker::new(Service::AniList, "secret").with_endpoint(&endpoint);
+
+        assert!(!tracker.update(154587, 4).unwrap());
+        seen.recv().unwrap();
+        assert!(seen.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+
+    #[test]
+    fn anilist_errors_are_reported() {
+        let (endpoint, _seen) = mock_tracker(vec![
+            r#"{"errors":[{"message":"Invalid token"}],"data":null}"#,
+        ]);
+        let tracker = Tracker::new(Service::AniList, "expired").with_endpoint(&endpoint);
+
+        let error = tracker.update(154587, 4).unwrap_err();
+        assert!(error.to_string().contains("Invalid token"));
+    }
+
+    #[test]
+    fn mal_pushes_progress() {
+        let (endpoint, seen) = mock_tracker(vec![
+            r#"{"id":52991,"title":"Sousou no Frieren"}"#,
+            r#"{"status":"watching","num_episodes_watched":4}"#,
+        ]);
+        let tracker = Tracker::new(Service::Mal, "secret").with_endpoint(&endpoint);
+
+        let trackers = [tracker];
+        let pushed = push(&trackers, &frieren(4));
+        assert!(matches!(pushed[..], [(_, Ok(true))]));
+
+        let query = seen.recv().unwrap();
+        assert_eq!(query.url, "/anime/52991?fields=my_list_status");
+        let update = seen.recv().unwrap();
+        assert_eq!(update.method, "PATCH");
+        assert_eq!(update.url, "/anime/52991/my_list_status");
+        assert_eq!(update.authorization, "Bearer secret");
+        assert_eq!(update.body, "status=watching&num_watched_episodes=4");
+    }
+
+    #[test]
+    fn history_keeps_highest_episode_and_known_ids() {
+        let mut history = History::default();
+        history.record("abc", frieren(5));
+        let show = history.record(
+            "abc",
+            Watched {
+                anilist_id: None,
+                ..frieren(3)
+            },
+        );
+        assert_eq!(show, &frieren(5));
+    }
+
+    #[test]
+    fn history_collects_finished_episodes() {
+        let mut history = History::default();
+        for episode in ["2", "1", "2"] {
+            history.record(
+                "abc",
+                Watched {
+                    finished: vec![episode.to_string()],
+                    ..frieren(2)
+                },
+            );
+        }
+        assert_eq!(history.shows["abc"].finished, ["2", "1"]);
+    }
+
+    #[test]
+    fn unnumbered_episodes_are_not_pushed() {
+        // nothing listens there, a request would fail
+        let tracker = Tracker::new(Service::AniList, "secret").with_endpoint("http://127.0.0.1:1");
+        let show = Watched {
+            finished: vec!["special".to_string()],
+            ..frieren(0)
+        };
+        assert!(push(&[tracker], &show).is_empty());
+    }
+
+    #[test]
+    fn episode_numbers() {
+        assert_eq!(episode_number("12"), Some(12));
+        assert_eq!(episode_number("12.5"), Some(12));
+        assert_eq!(episode_number("special"), None);
+    }
+}
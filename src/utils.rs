@@ -1,104 +1,257 @@
-pub fn decrypt_url(encrypted: &str) -> String {
-    let mut decrypted = String::with_capacity(encrypted.len() / 2);
+use std::{env, fmt, path::PathBuf};
 
-    let bytes = encrypted.as_bytes();
+/// key the allanime source urls are xored with before being hex encoded
+const URL_KEY: u8 = 0x38;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// hex strings come in pairs of digits
+    OddLength,
+    /// byte offset of a character that is not a hex digit
+    InvalidHex(usize),
+    /// the decoded bytes are not a utf-8 string
+    InvalidUtf8,
+}
 
-    // We iterate over the string in chunks of 2
-    for chunk in bytes.chunks(2) {
-        if chunk.len() < 2 {
-            break;
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::OddLength => write!(f, "odd number of hex digits"),
+            DecodeError::InvalidHex(at) => write!(f, "invalid hex digit at {}", at),
+            DecodeError::InvalidUtf8 => write!(f, "decoded url is not utf-8"),
         }
+    }
+}
+
+impl std::error::Error for DecodeError {}
 
-        let decoded_char_str = match chunk {
-            b"79" => "A",
-            b"7a" => "B",
-            b"7b" => "C",
-            b"7c" => "D",
-            b"7d" => "E",
-            b"7e" => "F",
-            b"7f" => "G",
-            b"70" => "H",
-            b"71" => "I",
-            b"72" => "J",
-            b"73" => "K",
-            b"74" => "L",
-            b"75" => "M",
-            b"76" => "N",
-            b"77" => "O",
-            b"68" => "P",
-            b"69" => "Q",
-            b"6a" => "R",
-            b"6b" => "S",
-            b"6c" => "T",
-            b"6d" => "U",
-            b"6e" => "V",
-            b"6f" => "W",
-            b"60" => "X",
-            b"61" => "Y",
-            b"62" => "Z",
-            b"59" => "a",
-            b"5a" => "b",
-            b"5b" => "c",
-            b"5c" => "d",
-            b"5d" => "e",
-            b"5e" => "f",
-            b"5f" => "g",
-            b"50" => "h",
-            b"51" => "i",
-            b"52" => "j",
-            b"53" => "k",
-            b"54" => "l",
-            b"55" => "m",
-            b"56" => "n",
-            b"57" => "o",
-            b"48" => "p",
-            b"49" => "q",
-            b"4a" => "r",
-            b"4b" => "s",
-            b"4c" => "t",
-            b"4d" => "u",
-            b"4e" => "v",
-            b"4f" => "w",
-            b"40" => "x",
-            b"41" => "y",
-            b"42" => "z",
-            b"08" => "0",
-            b"09" => "1",
-            b"0a" => "2",
-            b"0b" => "3",
-            b"0c" => "4",
-            b"0d" => "5",
-            b"0e" => "6",
-            b"0f" => "7",
-            b"00" => "8",
-            b"01" => "9",
-            b"15" => "-",
-            b"16" => ".",
-            b"67" => "_",
-            b"46" => "~",
-            b"02" => ":",
-            b"17" => "/",
-            b"07" => "?",
-            b"1b" => "#",
-            b"63" => "[",
-            b"65" => "]",
-            b"78" => "@",
-            b"19" => "!",
-            b"1c" => "$",
-            b"1e" => "&",
-            b"10" => "(",
-            b"11" => ")",
-            b"12" => "*",
-            b"13" => "+",
-            b"14" => ",",
-            b"03" => ";",
-            b"05" => "=",
-            b"1d" => "%",
-            _ => "",
-        };
-
-        decrypted.push_str(decoded_char_str);
+/// Decode a source url given as `--` followed by hex, each byte xored with 0x38
+pub fn decrypt_url(encrypted: &str) -> Result<String, DecodeError> {
+    let bytes = encrypted.as_bytes();
+    if !bytes.len().is_multiple_of(2) {
+        return Err(DecodeError::OddLength);
     }
 
-    decrypted
+    let digit = |at: usize| {
+        (bytes[at] as char)
+            .to_digit(16)
+            .map(|d| d as u8)
+            .ok_or(DecodeError::InvalidHex(at))
+    };
+    let decrypted = (0..bytes.len())
+        .step_by(2)
+        .map(|at| Ok((digit(at)? << 4 | digit(at + 1)?) ^ URL_KEY))
+        .collect::<Result<Vec<u8>, DecodeError>>()?;
+
+    String::from_utf8(decrypted).map_err(|_| DecodeError::InvalidUtf8)
+}
+
+/// Inverse of [`decrypt_url`], without the `--` prefix
+#[cfg_attr(not(test), allow(dead_code))]
+pub fn encrypt_url(url: &str) -> String {
+    url.bytes()
+        .map(|b| format!("{:02x}", b ^ URL_KEY))
+        .collect()
+}
+
+/// Days since the unix epoch for a date of the proleptic gregorian calendar
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// (year, month, day) for a count of days since the unix epoch
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Format unix time as `YYYY-MM-DD HH:MM` in utc
+pub fn format_unix(secs: i64) -> String {
+    let (year, month, day) = civil_from_days(secs.div_euclid(86400));
+    let rem = secs.rem_euclid(86400);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60
+    )
+}
+
+/// Day of the week of a unix time, in UTC
+pub fn weekday(secs: i64) -> &'static str {
+    // 1970-01-01 was a thursday
+    const DAYS: [&str; 7] = [
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+    ];
+    DAYS[secs.div_euclid(86400).rem_euclid(7) as usize]
+}
+
+/// Times the words of `query` appear in `text`, ignoring case
+pub fn count_matches(text: &str, query: &str) -> usize {
+    let text = text.to_lowercase();
+    query
+        .split_whitespace()
+        .map(|word| text.matches(&word.to_lowercase()).count())
+        .sum()
+}
+
+/// Extension of the last path segment of `url`, without its query or fragment
+pub fn url_extension(url: &str) -> Option<&str> {
+    let path = url.split(['?', '#']).next()?;
+    let segment = path.rsplit('/').next()?;
+    segment.rsplit_once('.').map(|(_, ext)| ext)
+}
+
+/// `value` if it is safe to put in a file name, else `fallback`
+pub fn file_name_part<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    let safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if safe { value } else { fallback }
+}
+
+/// Index of `wanted` in `episodes`, by name or else by number so `12.0` finds `12`
+pub fn find_episode(episodes: &[String], wanted: &str) -> Option<usize> {
+    let number = wanted.parse::<f64>().ok();
+    episodes.iter().position(|ep| ep == wanted).or_else(|| {
+        episodes
+            .iter()
+            .position(|ep| number.is_some() && ep.parse::<f64>().ok() == number)
+    })
+}
+
+/// Directory sho reads its config from, `$XDG_CONFIG_HOME/sho` or the platform equivalent
+pub fn config_dir() -> Option<PathBuf> {
+    #[cfg(unix)]
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+    // windows
+    #[cfg(not(unix))]
+    let base = env::var_os("APPDATA").map(PathBuf::from);
+
+    base.map(|dir| dir.join("sho"))
+}
+
+/// Directory sho keeps its data in, such as downloaded episodes
+pub fn data_dir() -> Option<PathBuf> {
+    #[cfg(unix)]
+    let base = env::var_os("XDG_DATA_HOME").map(PathBuf::from).or_else(|| {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+    });
+
+    // windows
+    #[cfg(not(unix))]
+    let base = env::var_os("LOCALAPPDATA").map(PathBuf::from);
+
+    base.map(|dir| dir.join("sho"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn decrypts_source_url() {
+        assert_eq!(
+            decrypt_url("175948514e4c4f57175b54575b53").as_deref(),
+            Ok("/apivtwo/clock")
+        );
+    }
+
+    #[test]
+    fn decrypts_uppercase_and_characters_outside_the_old_table() {
+        assert_eq!(decrypt_url("1F0404").as_deref(), Ok("'<<"));
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(weekday(0), "Thursday");
+        // 2024-06-02 23:59 UTC
+        assert_eq!(weekday(1717372740), "Sunday");
+        assert_eq!(weekday(-1), "Wednesday");
+    }
+
+    #[test]
+    fn counts_each_word_of_the_query() {
+        let text = "A mage and her apprentice. The Mage outlives her party.";
+        assert_eq!(count_matches(text, "mage"), 2);
+        assert_eq!(count_matches(text, "her  party"), 3);
+        assert_eq!(count_matches(text, ""), 0);
+    }
+
+    #[test]
+    fn extension_of_the_last_path_segment() {
+        assert_eq!(
+            url_extension("https://x.com/subs/en.vtt?t=1#a"),
+            Some("vtt")
+        );
+        assert_eq!(url_extension("https://x.com/subs/abc"), None);
+        assert_eq!(url_extension("https://x.com/subs/abc?f=a.srt"), None);
+    }
+
+    #[test]
+    fn unsafe_file_name_parts_fall_back() {
+        assert_eq!(file_name_part("pt-BR", "und"), "pt-BR");
+        assert_eq!(file_name_part("../..", "und"), "und");
+        assert_eq!(file_name_part("com/subs/abc", "vtt"), "vtt");
+        assert_eq!(file_name_part("", "und"), "und");
+    }
+
+    #[test]
+    fn finds_episodes_by_name_or_number() {
+        let episodes: Vec<String> = ["105", "1005", "1050", "12.5", "SP"]
+            .iter()
+            .map(|ep| ep.to_string())
+            .collect();
+        assert_eq!(find_episode(&episodes, "1050"), Some(2));
+        assert_eq!(find_episode(&episodes, "1050.0"), Some(2));
+        assert_eq!(find_episode(&episodes, "105"), Some(0));
+        assert_eq!(find_episode(&episodes, "12.50"), Some(3));
+        assert_eq!(find_episode(&episodes, "SP"), Some(4));
+        assert_eq!(find_episode(&episodes, "1051"), None);
+        assert_eq!(find_episode(&episodes, "10"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_hex() {
+        assert_eq!(decrypt_url("175"), Err(DecodeError::OddLength));
+        assert_eq!(decrypt_url("17zz"), Err(DecodeError::InvalidHex(2)));
+        assert_eq!(decrypt_url("c7"), Err(DecodeError::InvalidUtf8));
+    }
+
+    proptest! {
+        #[test]
+        fn round_trips_any_string(url in any::<String>()) {
+            prop_assert_eq!(decrypt_url(&encrypt_url(&url)), Ok(url));
+        }
+
+        #[test]
+        fn decrypts_uppercase_hex(url in "\\PC*") {
+            prop_assert_eq!(decrypt_url(&encrypt_url(&url).to_uppercase()), Ok(url));
+        }
+    }
 }
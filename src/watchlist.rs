@@ -0,0 +1,151 @@
+//! Followed shows and the episodes known of them, kept in `watchlist.json`
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+use crate::{api::Mode, utils::data_dir};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Followed {
+    pub name: String,
+    /// translation the show is followed in
+    pub mode: Mode,
+    /// episodes that were out at the last check
+    pub episodes: Vec<String>,
+    /// episodes found by a check that were not looked at yet
+    #[serde(default)]
+    pub unseen: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Watchlist {
+    path: Option<PathBuf>,
+    /// keyed by allanime show id
+    pub shows: BTreeMap<String, Followed>,
+}
+
+impl Watchlist {
+    pub fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("watchlist.json"))
+    }
+
+    /// Read the watchlist, a missing file is an empty watchlist
+    pub fn load(path: Option<PathBuf>) -> Result<Self, Box<dyn std::error::Error>> {
+        let shows = match path.as_deref().map(fs::read_to_string) {
+            Some(Ok(content)) => serde_json::from_str(&content)?,
+            Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => BTreeMap::new(),
+        };
+        Ok(Watchlist { path, shows })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.shows)?)?;
+        Ok(())
+    }
+
+    /// Follow the show or stop following it, true when it is followed now
+    pub fn toggle(&mut self, id: &str, show: Followed) -> bool {
+        if self.shows.remove(id).is_some() {
+            return false;
+        }
+        self.shows.insert(id.to_string(), show);
+        true
+    }
+
+    /// Remember the current `episodes` of a followed show, returning the ones
+    /// that were not out at the last check
+    pub fn update(&mut self, id: &str, episodes: &[String]) -> Vec<String> {
+        let Some(show) = self.shows.get_mut(id) else {
+            return Vec::new();
+        };
+
+        let new: Vec<String> = episodes
+            .iter()
+            .filter(|ep| !show.episodes.contains(ep))
+            .cloned()
+            .collect();
+        for ep in &new {
+            if !show.unseen.contains(ep) {
+                show.unseen.push(ep.clone());
+            }
+        }
+        show.episodes = episodes.to_vec();
+        new
+    }
+
+    /// Clear the new episodes badge, true when there was one
+    pub fn mark_seen(&mut self, id: &str) -> bool {
+        self.shows
+            .get_mut(id)
+            .is_some_and(|show| !std::mem::take(&mut show.unseen).is_empty())
+    }
+
+    /// Number of new episodes not looked at yet
+    pub fn unseen(&self, id: &str) -> usize {
+        self.shows.get(id).map_or(0, |show| show.unseen.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn episodes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|ep| ep.to_string()).collect()
+    }
+
+    fn watchlist() -> Watchlist {
+        let mut watchlist = Watchlist::default();
+        watchlist.toggle(
+            "abc",
+            Followed {
+                name: "Frieren".to_string(),
+                mode: Mode::Dub,
+                episodes: episodes(&["1", "2"]),
+                unseen: Vec::new(),
+            },
+        );
+        watchlist
+    }
+
+    #[test]
+    fn finds_new_episodes() {
+        let mut watchlist = watchlist();
+        assert_eq!(watchlist.update("abc", &episodes(&["1", "2", "3"])), ["3"]);
+        assert!(
+            watchlist
+                .update("abc", &episodes(&["1", "2", "3"]))
+                .is_empty()
+        );
+        assert_eq!(
+            watchlist.update("abc", &episodes(&["1", "2", "3", "4"])),
+            ["4"]
+        );
+        assert_eq!(watchlist.unseen("abc"), 2);
+        assert!(watchlist.update("unknown", &episodes(&["1"])).is_empty());
+    }
+
+    #[test]
+    fn marks_seen() {
+        let mut watchlist = watchlist();
+        watchlist.update("abc", &episodes(&["1", "2", "3"]));
+        assert!(watchlist.mark_seen("abc"));
+        assert!(!watchlist.mark_seen("abc"));
+        assert_eq!(watchlist.unseen("abc"), 0);
+    }
+
+    #[test]
+    fn toggles() {
+        let mut watchlist = watchlist();
+        let show = watchlist.shows["abc"].clone();
+        assert!(!watchlist.toggle("abc", show.clone()));
+        assert!(watchlist.toggle("abc", show));
+    }
+}